@@ -2,7 +2,7 @@ use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 use stardust_xr_asteroids::{
 	ClientState, CustomElement, Element, Migrate, Reify, Transformable, client,
-	elements::{Button, Reparentable, Spatial, Text},
+	elements::{Align, Button, Reparentable, Stack, Text},
 };
 use stardust_xr_fusion::{
 	drawable::{XAlign, YAlign},
@@ -39,36 +39,40 @@ impl ClientState for State {
 }
 impl Reify for State {
 	fn reify(&self) -> impl Element<Self> {
-		Reparentable::default()
-			.build()
-			.child(
-				LabeledButton::new(|state: &mut State| {
-					state.list.push(format!("List item {}", state.list.len()));
-				})
-				.height(0.01)
-				.padding(0.0025)
-				.label("add")
-				.pos([
-					-0.03, 0.02, 0.0,
-				])
-				.build(),
-			)
-			.child(
-				LabeledButton::new(|state: &mut State| {
-					state.list.pop();
-				})
-				.height(0.01)
-				.padding(0.0025)
-				.label("remove")
-				.pos([0.03, 0.02, 0.0])
+		Reparentable::default().build().child(
+			Stack::column()
+				.gap(0.0025)
+				.child(
+					Stack::row()
+						.gap(0.005)
+						.child(
+							LabeledButton::new(|state: &mut State| {
+								state.list.push(format!("List item {}", state.list.len()));
+							})
+							.height(0.01)
+							.padding(0.0025)
+							.label("add")
+							.build(),
+						)
+						.child(
+							LabeledButton::new(|state: &mut State| {
+								state.list.pop();
+							})
+							.height(0.01)
+							.padding(0.0025)
+							.label("remove")
+							.build(),
+						)
+						.build(),
+				)
+				.children(
+					self.list
+						.iter()
+						.enumerate()
+						.map(|(i, t)| make_list_item(i, t)),
+				)
 				.build(),
-			)
-			.children(
-				self.list
-					.iter()
-					.enumerate()
-					.map(|(i, t)| make_list_item(i, t)),
-			)
+		)
 	}
 }
 
@@ -120,28 +124,22 @@ impl Transformable for LabeledButton {
 
 fn make_list_item(index: usize, text: &String) -> impl Element<State> {
 	let size = 0.01;
-	let padding = 0.0025;
-	Spatial::default()
-		.pos([
-			0.0,
-			(index as f32) * -(size + padding),
-			0.0,
-		])
-		.build()
+	Stack::row()
+		.gap(0.0025)
+		.align(Align::Center)
 		.child(
 			Button::new(move |state: &mut State| {
 				state.list.remove(index);
 			})
 			.size([size; 2])
-			.pos([-0.05, 0.0, 0.0])
-			.build(),
-		)
-		.child(
-			Text::new("-")
-				.character_height(size)
-				.align_x(XAlign::Center)
-				.pos([-0.05, 0.0, 0.0])
-				.build(),
+			.build()
+			.child(
+				Text::new("-")
+					.character_height(size)
+					.align_x(XAlign::Center)
+					.align_y(YAlign::Center)
+					.build(),
+			),
 		)
 		.child(
 			Text::new(text)
@@ -149,4 +147,5 @@ fn make_list_item(index: usize, text: &String) -> impl Element<State> {
 				.align_x(XAlign::Left)
 				.build(),
 		)
+		.build()
 }
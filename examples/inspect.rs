@@ -0,0 +1,59 @@
+//! Prints the element tree of every client running with `ASTEROIDS_INSPECT=1`.
+//! Pass an app ID to only print that client.
+
+use stardust_xr_asteroids::inspector::{BUS_NAME_PREFIX, InspectedNode, InspectorProxy};
+use std::collections::HashMap;
+use zbus::{Connection, fdo::DBusProxy};
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> zbus::Result<()> {
+	let filter = std::env::args().nth(1);
+	let connection = Connection::session().await?;
+	let names = DBusProxy::new(&connection).await?.list_names().await?;
+
+	for name in names {
+		let Some(app_id) = name.strip_prefix(BUS_NAME_PREFIX) else {
+			continue;
+		};
+		if filter.as_deref().is_some_and(|filter| filter != app_id) {
+			continue;
+		}
+		let inspector = InspectorProxy::builder(&connection)
+			.destination(name.to_string())?
+			.build()
+			.await?;
+		let nodes = inspector.tree().await?;
+
+		println!("{app_id} ({} elements)", nodes.len());
+		let mut children: HashMap<&str, Vec<&InspectedNode>> = HashMap::new();
+		for node in &nodes {
+			children.entry(node.parent.as_str()).or_default().push(node);
+		}
+		for siblings in children.values_mut() {
+			siblings.sort_by(|a, b| a.path.cmp(&b.path));
+		}
+		// roots are whatever's parented under a path that isn't an element
+		let mut roots = children
+			.keys()
+			.filter(|parent| !nodes.iter().any(|node| node.path == **parent))
+			.copied()
+			.collect::<Vec<_>>();
+		roots.sort();
+		for root in roots {
+			print_children(&children, root, 1);
+		}
+	}
+	Ok(())
+}
+
+fn print_children(children: &HashMap<&str, Vec<&InspectedNode>>, parent: &str, depth: usize) {
+	for node in children.get(parent).into_iter().flatten() {
+		let indent = "  ".repeat(depth);
+		let (x, y, z) = node.translation;
+		println!(
+			"{indent}{} key={:x} pos=[{x:.3}, {y:.3}, {z:.3}] {}",
+			node.element_type, node.key, node.debug
+		);
+		print_children(children, &node.path, depth + 1);
+	}
+}
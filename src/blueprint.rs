@@ -0,0 +1,119 @@
+use crate::{Context, Element, Reify, ValidState, element::ElementDiffer};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A declared element and its children, as produced by `reify`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlueprintNode {
+	/// Element name without the namespace or generics, e.g. `Button`
+	pub element_type: String,
+	/// `Debug` output of the element declaration
+	pub debug: String,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub children: Vec<BlueprintNode>,
+}
+
+/// The element tree a state reifies into, without creating anything, for golden tests of `Reify` impls.
+///
+/// Wrappers like `Mapped`, `Memo` and `Provider` don't show up themselves, only what they contain.
+/// Animations show up settled at their target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Blueprint(pub Vec<BlueprintNode>);
+impl Blueprint {
+	pub fn of<State: Reify>(state: &State, context: &Context) -> Self {
+		Self::of_element(&state.reify(), context)
+	}
+	pub fn of_element<State: ValidState>(element: &impl Element<State>, context: &Context) -> Self {
+		let mut nodes = Vec::new();
+		element.blueprint(context, &mut nodes);
+		Blueprint(nodes)
+	}
+
+	pub fn to_ron(&self) -> String {
+		ron::ser::to_string_pretty(self, PrettyConfig::default().indentor("\t".to_string()))
+			.unwrap()
+	}
+
+	/// Compare against the RON snapshot at `path`, panicking with both versions if they differ.
+	///
+	/// The snapshot gets written instead when it doesn't exist yet or `ASTEROIDS_UPDATE_SNAPSHOTS` is set.
+	#[track_caller]
+	pub fn assert_snapshot(&self, path: impl AsRef<Path>) {
+		let path = path.as_ref();
+		let current = self.to_ron();
+		if std::env::var_os("ASTEROIDS_UPDATE_SNAPSHOTS").is_some() || !path.exists() {
+			if let Some(parent) = path.parent() {
+				std::fs::create_dir_all(parent).unwrap();
+			}
+			std::fs::write(path, &current).unwrap();
+			return;
+		}
+
+		let snapshot = std::fs::read_to_string(path).unwrap();
+		if snapshot != current {
+			panic!(
+				"blueprint doesn't match snapshot {}, rerun with ASTEROIDS_UPDATE_SNAPSHOTS=1 if this is expected\n\nsnapshot:\n{snapshot}\n\ncurrent:\n{current}",
+				path.display()
+			);
+		}
+	}
+}
+
+#[tokio::test]
+async fn asteroids_blueprint() {
+	use crate::{
+		CustomElement, Memo, Provider,
+		elements::{Spatial, Text, Timeout},
+	};
+	use std::time::Duration;
+
+	struct TestState {
+		items: Vec<&'static str>,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Provider::new(
+				(),
+				Spatial::default()
+					.build()
+					.children(self.items.iter().map(|item| Text::new(*item).build()))
+					.child(Memo::new(self.items.len(), |len| {
+						Timeout::new(Duration::from_secs(*len as u64), |_: &mut Self| {})
+					})),
+			)
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let mut state = TestState {
+		items: vec!["a", "b"],
+	};
+	let blueprint = Blueprint::of(&state, &context);
+	let [spatial] = blueprint.0.as_slice() else {
+		panic!("expected a single root");
+	};
+	assert_eq!(spatial.element_type, "Spatial");
+	let types = spatial
+		.children
+		.iter()
+		.map(|node| node.element_type.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(types, ["Text", "Text", "Timeout"]);
+	assert!(spatial.children[1].debug.contains("\"b\""));
+
+	let ron = blueprint.to_ron();
+	assert_eq!(ron::from_str::<Blueprint>(&ron).unwrap(), blueprint);
+
+	let snapshot =
+		std::env::temp_dir().join(format!("asteroids_blueprint_{}.ron", std::process::id()));
+	let _ = std::fs::remove_file(&snapshot);
+	blueprint.assert_snapshot(&snapshot);
+	blueprint.assert_snapshot(&snapshot);
+	state.items.pop();
+	let changed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+		Blueprint::of(&state, &context).assert_snapshot(&snapshot)
+	}));
+	assert!(changed.is_err());
+	let _ = std::fs::remove_file(&snapshot);
+}
@@ -1,6 +1,9 @@
 use crate::{
-	Context, Projector, Reify,
-	util::{Migrate, RonFile},
+	Context, Element, Projector, Reify, Transformable,
+	elements::FileWatch,
+	inspector::{self, InspectorHandle},
+	recording::Recorder,
+	util::{Format, History, Migrate, MigrateError, SavedState},
 };
 use serde::{Serialize, de::DeserializeOwned};
 use stardust_xr_fusion::{
@@ -8,48 +11,192 @@ use stardust_xr_fusion::{
 	node::NodeType,
 	objects::connect_client,
 	root::{FrameInfo, RootAspect, RootEvent},
+	spatial::{Spatial, SpatialRef, Transform},
 };
 use stardust_xr_molecules::accent_color::AccentColor;
-use std::fs::read_to_string;
+use std::{
+	path::{Path, PathBuf},
+	time::Duration,
+};
 use tokio::signal::unix::{SignalKind, signal};
 
 /// Represents a client that connects to the stardust server
 pub trait ClientState: Reify + Default + Migrate + Serialize + DeserializeOwned {
 	/// App ID, inverse domain name e.g. "org.stardustxr.asteroids_test".
 	const APP_ID: &'static str;
+	/// How the state is saved, both for the server's saved state and the initial state file.
+	/// Changing it makes previously saved state unreadable.
+	const FORMAT: Format = Format::Ron;
 
 	/// Update the client state when newly launched (e.g. for program arguments)
 	fn initial_state_update(&mut self) {}
 	fn on_frame(&mut self, _info: &FrameInfo) {}
+	/// A cheap summary of everything `reify` depends on, e.g. a hash of the state or a counter bumped in your callbacks.
+	/// When it's the same as after the last update, reifying and diffing get skipped for that frame.
+	/// `None` (the default) updates every frame.
+	fn change_fingerprint(&self) -> Option<u64> {
+		None
+	}
+	/// Extra projectors next to the main one, e.g. a detachable palette, each reconciled on its own cadence.
+	/// Called once after the state is loaded.
+	fn views(&self) -> Vec<View<Self>> {
+		Vec::new()
+	}
+	/// Undo/redo for the state, `None` (the default) to not keep any.
+	/// The history has to live in the state with `#[serde(skip)]`, see [`History`].
+	fn history(&mut self) -> Option<&mut History> {
+		None
+	}
+	/// Called with the freshly loaded state when the initial state file changes while running.
+	/// Replaces the running state but keeps its [`History`] by default,
+	/// override to merge only some of it (e.g. layout constants).
+	fn reload_initial_state(&mut self, reloaded: Self) {
+		// the history isn't part of the file so it moves over to the reloaded state
+		let history = self.history().map(std::mem::take);
+		*self = reloaded;
+		if let (Some(history), Some(reloaded_history)) = (history, self.history()) {
+			*reloaded_history = history;
+		}
+	}
 }
 
-fn initial_state<State: ClientState>() -> State {
+type CreateProjector<State> =
+	Box<dyn FnOnce(&State, &Context, SpatialRef, PathBuf) -> Projector<State> + Send + Sync>;
+
+/// A projector with its own root and reify function that shares the client's state and context.
+pub struct View<State: ClientState> {
+	root_path: PathBuf,
+	transform: Transform,
+	update_interval: Duration,
+	create: CreateProjector<State>,
+}
+impl<State: ClientState> View<State> {
+	/// `root_path` has to be unique among the client's views, the main projector uses `/`.
+	pub fn new<E: Element<State>>(
+		root_path: impl Into<PathBuf>,
+		reify: impl Fn(&State) -> E + Send + Sync + 'static,
+	) -> Self {
+		View {
+			root_path: root_path.into(),
+			transform: Transform::identity(),
+			update_interval: Duration::ZERO,
+			create: Box::new(move |state, context, parent, root_path| {
+				Projector::create_with(state, context, parent, root_path, reify)
+			}),
+		}
+	}
+	/// Minimum time between updates, zero (the default) to update every frame the state changed.
+	/// Frames still reach the view's elements every frame so input keeps working.
+	pub fn update_interval(mut self, update_interval: Duration) -> Self {
+		self.update_interval = update_interval;
+		self
+	}
+}
+impl<State: ClientState> Transformable for View<State> {
+	fn transform(&self) -> &Transform {
+		&self.transform
+	}
+	fn transform_mut(&mut self) -> &mut Transform {
+		&mut self.transform
+	}
+}
+
+/// A projector in the event loop along with when it needs updating
+struct Projection<State: ClientState> {
+	projector: Projector<State>,
+	/// Keeps a view's root spatial alive
+	_root: Option<Spatial>,
+	update_interval: f32,
+	since_update: f32,
+	last_fingerprint: Option<u64>,
+}
+impl<State: ClientState> Projection<State> {
+	fn new(
+		projector: Projector<State>,
+		root: Option<Spatial>,
+		update_interval: Duration,
+		state: &State,
+	) -> Self {
+		Projection {
+			projector,
+			_root: root,
+			update_interval: update_interval.as_secs_f32(),
+			since_update: 0.0,
+			last_fingerprint: state.change_fingerprint(),
+		}
+	}
+	fn frame(&mut self, context: &Context, info: &FrameInfo, state: &mut State) {
+		self.since_update += info.delta;
+		self.projector.frame(context, info, state);
+	}
+	/// Returns if it actually updated
+	fn update(&mut self, context: &Context, state: &mut State, fingerprint: Option<u64>) -> bool {
+		if self.since_update < self.update_interval {
+			return false;
+		}
+		let unchanged = fingerprint.is_some() && fingerprint == self.last_fingerprint;
+		if unchanged && !self.projector.update_requested() {
+			return false;
+		}
+		self.projector.update(context, state);
+		self.last_fingerprint = fingerprint;
+		self.since_update = 0.0;
+		true
+	}
+	/// Update next time regardless of the fingerprint, e.g. when the state got replaced
+	fn invalidate(&mut self) {
+		self.last_fingerprint = None;
+		self.since_update = self.update_interval;
+	}
+}
+
+fn initial_state_path<State: ClientState>() -> PathBuf {
 	// this is a dumb heuristic for determining if it's installed or not, may wanna replace
 	#[cfg(debug_assertions)]
-	let initial_state_path =
-		std::path::PathBuf::from("/tmp/asteroids_config").join(State::APP_ID.to_string() + ".ron");
+	let initial_state_path = std::path::PathBuf::from("/tmp/asteroids_config")
+		.join(State::APP_ID.to_string() + "." + State::FORMAT.extension());
 	#[cfg(not(debug_assertions))]
 	let initial_state_path = directories::BaseDirs::new()
 		.unwrap()
 		.config_dir()
 		.join(State::APP_ID)
-		.join("initial_state.ron");
-	let mut state = match read_to_string(&initial_state_path).ok().map(RonFile) {
-		Some(initial_state_string) => State::deserialize_with_migrate(&initial_state_string)
-			.unwrap_or_else(|_| State::default()),
-		None => State::default(),
+		.join(format!("initial_state.{}", State::FORMAT.extension()));
+	initial_state_path
+}
+fn initial_state<State: ClientState>(initial_state_path: &Path) -> Result<State, MigrateError> {
+	let mut state = match std::fs::read(initial_state_path) {
+		Ok(data) => State::deserialize_with_migrate(&saved_state::<State>(data))?,
+		Err(_) => State::default(),
 	};
 	if !initial_state_path.exists() {
 		let _ = std::fs::create_dir_all(initial_state_path.parent().unwrap());
-		let _ = std::fs::write(&initial_state_path, ron::to_string(&state).unwrap());
+		if let Ok(saved) = SavedState::serialize(State::FORMAT, &state) {
+			let _ = std::fs::write(initial_state_path, saved.data);
+		}
 	}
 	state.initial_state_update();
-	state
+	Ok(state)
+}
+/// Reload the initial state file after it changed, merging it into `state`
+fn reload_initial_state<State: ClientState>(state: &mut State, initial_state_path: &Path) {
+	let Ok(data) = std::fs::read(initial_state_path) else {
+		return;
+	};
+	match State::deserialize_with_migrate(&saved_state::<State>(data)) {
+		Ok(mut reloaded) => {
+			reloaded.initial_state_update();
+			state.reload_initial_state(reloaded);
+		}
+		Err(error) => tracing::warn!("Couldn't reload {}: {error}", initial_state_path.display()),
+	}
 }
 
-async fn state<State: ClientState>(client: &mut Client) -> Option<State> {
+/// The state to start with, along with the saved state if it couldn't be loaded.
+/// That gets handed back to the server on save instead of being overwritten,
+/// so a fixed migration can still load it later.
+async fn state<State: ClientState>(client: &mut Client) -> Option<(State, Option<Vec<u8>>)> {
 	if let Some(state) = load_dev_state() {
-		return Some(state);
+		return Some((state, None));
 	}
 
 	let saved_state = client
@@ -58,11 +205,28 @@ async fn state<State: ClientState>(client: &mut Client) -> Option<State> {
 		.ok()?
 		.ok()?;
 
-	let state = saved_state
-		.data
-		.and_then(|m| ron::from_str(&String::from_utf8(m).ok()?).ok())
-		.unwrap_or_else(initial_state);
-	Some(state)
+	let mut unreadable = None;
+	if let Some(data) = saved_state.data {
+		match State::deserialize_with_migrate(&saved_state::<State>(data.clone())) {
+			Ok(state) => return Some((state, None)),
+			Err(error) => {
+				tracing::error!("Couldn't load saved state, keeping it as is: {error}");
+				unreadable = Some(data);
+			}
+		}
+	}
+	// state that doesn't load shouldn't keep the client from starting, and its file is left alone
+	let initial_state_path = initial_state_path::<State>();
+	let state = initial_state(&initial_state_path).unwrap_or_else(|error| {
+		tracing::error!(
+			"Couldn't load {}, starting from the default state: {error}",
+			initial_state_path.display()
+		);
+		let mut state = State::default();
+		state.initial_state_update();
+		state
+	});
+	Some((state, unreadable))
 }
 
 fn load_dev_state<State: ClientState>() -> Option<State> {
@@ -71,10 +235,12 @@ fn load_dev_state<State: ClientState>() -> Option<State> {
 	}
 
 	let initial_state_path = std::path::PathBuf::from("/tmp/asteroids_config")
-		.join(State::APP_ID.to_string() + "_dev.ron");
+		.join(State::APP_ID.to_string() + "_dev." + State::FORMAT.extension());
 
-	let serialized = std::fs::read_to_string(initial_state_path).ok()?;
-	ron::from_str(&serialized).ok()
+	let data = std::fs::read(initial_state_path).ok()?;
+	State::deserialize_with_migrate(&saved_state::<State>(data))
+		.inspect_err(|error| tracing::warn!("Couldn't load dev state: {error}"))
+		.ok()
 }
 fn save_dev_state<State: ClientState>(state: &State) {
 	if std::env::var("ASTEROIDS_DEV").is_err() {
@@ -82,10 +248,18 @@ fn save_dev_state<State: ClientState>(state: &State) {
 	}
 
 	let initial_state_path = std::path::PathBuf::from("/tmp/asteroids_config")
-		.join(State::APP_ID.to_string() + "_dev.ron");
+		.join(State::APP_ID.to_string() + "_dev." + State::FORMAT.extension());
 
 	let _ = std::fs::create_dir_all(initial_state_path.parent().unwrap());
-	let _ = std::fs::write(&initial_state_path, ron::to_string(&state).unwrap());
+	if let Ok(saved) = SavedState::serialize(State::FORMAT, state) {
+		let _ = std::fs::write(&initial_state_path, saved.data);
+	}
+}
+fn saved_state<State: ClientState>(data: Vec<u8>) -> SavedState {
+	SavedState {
+		format: State::FORMAT,
+		data,
+	}
 }
 
 pub async fn run<State: ClientState>(resources: &[&std::path::Path]) {
@@ -99,23 +273,67 @@ pub async fn run<State: ClientState>(resources: &[&std::path::Path]) {
 	let dbus_connection = connect_client().await.unwrap();
 
 	let accent_color = AccentColor::new(dbus_connection.clone());
-	let context = Context {
-		dbus_connection,
-		accent_color,
-	};
+	let context = Context::new(dbus_connection, accent_color);
 
-	let Some(mut state): Option<State> = state(&mut client).await else {
+	let Some((mut state, unreadable_save)): Option<(State, _)> = state(&mut client).await else {
 		return;
 	};
 
 	dioxus_devtools::connect_subsecond();
 
-	let mut projector = Projector::create(
+	History::apply(&mut state);
+
+	let root = client.get_root().clone().as_spatial_ref();
+	let mut projections = vec![Projection::new(
+		Projector::create(&state, &context, root.clone(), "/".into()),
+		None,
+		Duration::ZERO,
 		&state,
-		&context,
-		client.get_root().clone().as_spatial_ref(),
-		"/".into(),
-	);
+	)];
+	for view in state.views() {
+		let view_root = match Spatial::create(&root, view.transform) {
+			Ok(view_root) => view_root,
+			Err(error) => {
+				tracing::error!(
+					"Couldn't create root of view {}: {error}",
+					view.root_path.display()
+				);
+				continue;
+			}
+		};
+		let projector = (view.create)(
+			&state,
+			&context,
+			view_root.clone().as_spatial_ref(),
+			view.root_path,
+		);
+		projections.push(Projection::new(
+			projector,
+			Some(view_root),
+			view.update_interval,
+			&state,
+		));
+	}
+
+	let inspector = if inspector::enabled() {
+		InspectorHandle::serve(&context.dbus_connection, State::APP_ID)
+			.await
+			.inspect_err(|error| tracing::warn!("Couldn't publish element tree: {error}"))
+			.ok()
+	} else {
+		None
+	};
+	let publish_tree = |projections: &[Projection<State>]| {
+		if let Some(inspector) = &inspector {
+			inspector.publish(projections.iter().filter_map(|p| p.projector.scene()));
+		}
+	};
+	publish_tree(&projections);
+	let mut recorder = Recorder::from_env(&state);
+	// so tweaks to the initial state show up without restarting, even if the file gets created later
+	let initial_state_path = initial_state_path::<State>();
+	let _ = std::fs::create_dir_all(initial_state_path.parent().unwrap());
+	let initial_state_watch = FileWatch::directory_entry(initial_state_path.clone());
 	let event_loop_future = client.sync_event_loop(|client, _| {
 		let mut frames = vec![];
 		while let Some(root_event) = client.get_root().recv_root_event() {
@@ -131,8 +349,15 @@ pub async fn run<State: ClientState>(resources: &[&std::path::Path]) {
 					frames.push(info);
 				}
 				RootEvent::SaveState { response } => {
+					// saved state that didn't load stays until a version that can load it runs
+					let data = match &unreadable_save {
+						Some(unreadable) => Some(unreadable.clone()),
+						None => SavedState::serialize(State::FORMAT, &state)
+							.ok()
+							.map(|saved| saved.data),
+					};
 					response.send_ok(stardust_xr_fusion::root::ClientState {
-						data: ron::to_string(&state).ok().map(|s| s.into_bytes()),
+						data,
 						root: client.get_root().id(),
 						spatial_anchors: Default::default(),
 					})
@@ -146,11 +371,34 @@ pub async fn run<State: ClientState>(resources: &[&std::path::Path]) {
 			tracing::warn!("Dropped {} frames!!", frames.len() - 1);
 		}
 
-		for frame in frames {
-			state.on_frame(&frame);
-			projector.frame(&context, &frame, &mut state);
+		for frame in &frames {
+			state.on_frame(frame);
+			for projection in &mut projections {
+				projection.frame(&context, frame, &mut state);
+			}
+		}
+		let mut replaced = History::apply(&mut state);
+		if initial_state_watch.take_modified() {
+			reload_initial_state(&mut state, &initial_state_path);
+			replaced = true;
+		}
+		if replaced {
+			for projection in &mut projections {
+				projection.invalidate();
+			}
+		}
+
+		let fingerprint = state.change_fingerprint();
+		let mut updated = false;
+		for projection in &mut projections {
+			updated |= projection.update(&context, &mut state, fingerprint);
+		}
+		if updated {
+			publish_tree(&projections);
+		}
+		if let Some(recorder) = &mut recorder {
+			recorder.record(&frames, &state);
 		}
-		projector.update(&context, &mut state);
 	});
 	let mut sigterm = signal(SignalKind::terminate()).unwrap();
 	// make sure we call Drop impls
@@ -162,3 +410,226 @@ pub async fn run<State: ClientState>(resources: &[&std::path::Path]) {
 	save_dev_state(&state);
 	_ = client.try_flush().await;
 }
+
+#[tokio::test]
+async fn asteroids_view_update_interval() {
+	use crate::{CustomElement, elements::Text};
+	use serde::Deserialize;
+
+	#[derive(Default, Serialize, Deserialize)]
+	struct TestState {
+		count: u32,
+	}
+	impl Migrate for TestState {
+		type Old = Self;
+	}
+	impl ClientState for TestState {
+		const APP_ID: &'static str = "org.asteroids.views";
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Text::new(self.count.to_string()).build()
+		}
+	}
+	fn shown(projection: &Projection<TestState>) -> String {
+		let scene = projection.projector.scene().unwrap();
+		scene.find("Text").next().unwrap().debug.clone()
+	}
+
+	let context = Context::headless().await.unwrap();
+	let info = FrameInfo {
+		delta: 0.5,
+		elapsed: 0.0,
+	};
+	let mut state = TestState::default();
+	let palette =
+		Projector::headless_with(&state, &context, "/palette".into(), |state: &TestState| {
+			Text::new(format!("palette {}", state.count)).build()
+		});
+	let mut projection = Projection::new(palette, None, Duration::from_secs(1), &state);
+	assert!(shown(&projection).contains("palette 0"));
+
+	state.count += 1;
+	projection.frame(&context, &info, &mut state);
+	assert!(!projection.update(&context, &mut state, None));
+	assert!(shown(&projection).contains("palette 0"));
+	projection.frame(&context, &info, &mut state);
+	assert!(projection.update(&context, &mut state, None));
+	assert!(shown(&projection).contains("palette 1"));
+}
+
+#[tokio::test]
+async fn asteroids_change_fingerprint() {
+	use crate::{CustomElement, elements::Text};
+	use serde::Deserialize;
+	use std::sync::{
+		Arc,
+		atomic::{AtomicUsize, Ordering},
+	};
+
+	#[derive(Default, Serialize, Deserialize)]
+	struct TestState {
+		count: u32,
+		/// bumped by hand, `None` fingerprint while 0
+		version: u64,
+	}
+	impl Migrate for TestState {
+		type Old = Self;
+	}
+	impl ClientState for TestState {
+		const APP_ID: &'static str = "org.asteroids.fingerprint";
+		fn change_fingerprint(&self) -> Option<u64> {
+			(self.version != 0).then_some(self.version)
+		}
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Text::new(self.count.to_string()).build()
+		}
+	}
+	fn shown(projection: &Projection<TestState>) -> String {
+		let scene = projection.projector.scene().unwrap();
+		scene.find("Text").next().unwrap().debug.clone()
+	}
+
+	let context = Context::headless().await.unwrap();
+	let info = FrameInfo {
+		delta: 0.5,
+		elapsed: 0.0,
+	};
+	let reifies = Arc::new(AtomicUsize::new(0));
+	let mut state = TestState {
+		count: 0,
+		version: 1,
+	};
+	let projector = Projector::headless_with(&state, &context, "/".into(), {
+		let reifies = reifies.clone();
+		move |state: &TestState| {
+			reifies.fetch_add(1, Ordering::Relaxed);
+			Text::new(format!("count {}", state.count)).build()
+		}
+	});
+	let mut projection = Projection::new(projector, None, Duration::ZERO, &state);
+	assert_eq!(reifies.load(Ordering::Relaxed), 1);
+
+	// same fingerprint as when the projection got created, nothing gets reified
+	state.count += 1;
+	projection.frame(&context, &info, &mut state);
+	let fingerprint = state.change_fingerprint();
+	assert!(!projection.update(&context, &mut state, fingerprint));
+	assert_eq!(reifies.load(Ordering::Relaxed), 1);
+	assert!(shown(&projection).contains("count 0"));
+
+	state.version += 1;
+	projection.frame(&context, &info, &mut state);
+	let fingerprint = state.change_fingerprint();
+	assert!(projection.update(&context, &mut state, fingerprint));
+	assert_eq!(reifies.load(Ordering::Relaxed), 2);
+	assert!(shown(&projection).contains("count 1"));
+
+	// a replaced state updates even with the same fingerprint
+	state.count += 1;
+	projection.invalidate();
+	let fingerprint = state.change_fingerprint();
+	assert!(projection.update(&context, &mut state, fingerprint));
+	assert_eq!(reifies.load(Ordering::Relaxed), 3);
+	assert!(shown(&projection).contains("count 2"));
+
+	// no fingerprint updates every time
+	state.version = 0;
+	state.count += 1;
+	for _ in 0..2 {
+		projection.frame(&context, &info, &mut state);
+		let fingerprint = state.change_fingerprint();
+		assert!(projection.update(&context, &mut state, fingerprint));
+	}
+	assert_eq!(reifies.load(Ordering::Relaxed), 5);
+	assert!(shown(&projection).contains("count 3"));
+}
+
+#[tokio::test]
+async fn asteroids_reload_initial_state() {
+	use crate::{CustomElement, elements::Text};
+	use serde::Deserialize;
+
+	#[derive(Default, Serialize, Deserialize)]
+	struct TestState {
+		spacing: f32,
+		#[serde(skip)]
+		opened: bool,
+	}
+	impl Migrate for TestState {
+		type Old = Self;
+	}
+	impl ClientState for TestState {
+		const APP_ID: &'static str = "org.asteroids.reload";
+
+		fn reload_initial_state(&mut self, reloaded: Self) {
+			self.spacing = reloaded.spacing;
+		}
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Text::new(self.spacing.to_string()).build()
+		}
+	}
+
+	let dir = std::env::temp_dir().join(format!("asteroids_reload_{}", std::process::id()));
+	let path = dir.join("initial_state.ron");
+	// a missing file gets written with the default state
+	let mut state = initial_state::<TestState>(&path).unwrap();
+	assert!(path.exists());
+	state.opened = true;
+	std::fs::write(&path, "(spacing: 0.25)").unwrap();
+	reload_initial_state(&mut state, &path);
+	assert_eq!(state.spacing, 0.25);
+	assert!(state.opened);
+
+	// a broken file keeps the running state
+	std::fs::write(&path, "(spacing: ").unwrap();
+	reload_initial_state(&mut state, &path);
+	assert_eq!(state.spacing, 0.25);
+	let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn asteroids_reload_keeps_history() {
+	use crate::{CustomElement, elements::Text};
+	use serde::Deserialize;
+
+	#[derive(Default, Serialize, Deserialize)]
+	struct TestState {
+		spacing: f32,
+		#[serde(skip)]
+		history: History,
+	}
+	impl Migrate for TestState {
+		type Old = Self;
+	}
+	impl ClientState for TestState {
+		const APP_ID: &'static str = "org.asteroids.reload_history";
+
+		fn history(&mut self) -> Option<&mut History> {
+			Some(&mut self.history)
+		}
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Text::new(self.spacing.to_string()).build()
+		}
+	}
+
+	let mut state = TestState::default();
+	History::apply(&mut state);
+	state.spacing = 0.5;
+	state.history.commit();
+	History::apply(&mut state);
+	assert!(state.history.can_undo());
+
+	state.reload_initial_state(TestState {
+		spacing: 0.25,
+		..Default::default()
+	});
+	assert_eq!(state.spacing, 0.25);
+	assert!(state.history.can_undo());
+}
@@ -1,7 +1,147 @@
+use rustc_hash::FxHashSet;
 use stardust_xr_molecules::accent_color::AccentColor;
+use std::{
+	any::{Any, TypeId},
+	sync::{Arc, Mutex},
+};
 use zbus::Connection;
+#[cfg(any(test, feature = "headless"))]
+use {
+	stardust_xr_fusion::{Client, node::NodeType, spatial::SpatialRef},
+	tokio::net::UnixStream,
+	zbus::{Guid, connection::Builder},
+};
+
+struct ProvidedValue {
+	type_id: TypeId,
+	value: Arc<dyn Any + Send + Sync>,
+	changed: bool,
+}
 
 pub struct Context {
 	pub dbus_connection: Connection,
 	pub accent_color: AccentColor,
+	/// Values from the [`crate::Provider`]s above the element currently being created/diffed/framed
+	providers: Mutex<Vec<ProvidedValue>>,
+	/// Types read through [`Context::provided`], one set per [`crate::Memo`] being created/diffed
+	consumed: Mutex<Vec<FxHashSet<TypeId>>>,
+	#[cfg(any(test, feature = "headless"))]
+	headless: Option<HeadlessServer>,
+}
+impl Context {
+	pub fn new(dbus_connection: Connection, accent_color: AccentColor) -> Self {
+		Context {
+			dbus_connection,
+			accent_color,
+			providers: Mutex::default(),
+			consumed: Mutex::default(),
+			#[cfg(any(test, feature = "headless"))]
+			headless: None,
+		}
+	}
+
+	/// A context backed by a private peer-to-peer D-Bus connection instead of the session bus
+	/// and a fusion client connected to a fake server, for driving a headless [`crate::Projector`] in tests.
+	#[cfg(any(test, feature = "headless"))]
+	pub async fn headless() -> zbus::Result<Self> {
+		let (client_stream, server_stream) = UnixStream::pair()?;
+		let (dbus_connection, dbus_server) = tokio::try_join!(
+			Builder::unix_stream(client_stream).p2p().build(),
+			Builder::unix_stream(server_stream)
+				.server(Guid::generate())?
+				.p2p()
+				.build(),
+		)?;
+		let accent_color = AccentColor::new(dbus_connection.clone());
+		let mut context = Context::new(dbus_connection, accent_color);
+		context.headless = Some(HeadlessServer::new(dbus_server)?);
+		Ok(context)
+	}
+	/// Root of the fake server's scene graph, headless projectors get created under it
+	#[cfg(any(test, feature = "headless"))]
+	pub(crate) fn headless_root(&self) -> SpatialRef {
+		let headless = self.headless.as_ref();
+		let headless = headless.expect("headless projectors need a `Context::headless`");
+		headless.root.clone()
+	}
+
+	/// The value of the nearest [`crate::Provider<T>`] above the current element.
+	/// Valid in `CustomElement::create_inner`, `CustomElement::diff` and `CustomElement::frame`.
+	pub fn provided<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+		self.consume(TypeId::of::<T>());
+		let providers = self.providers.lock().unwrap();
+		providers
+			.iter()
+			.rev()
+			.find(|provided| provided.type_id == TypeId::of::<T>())
+			.and_then(|provided| provided.value.clone().downcast::<T>().ok())
+	}
+	/// If the nearest provider of any of `types` above the current element changed its value in this update.
+	pub(crate) fn providers_changed(&self, types: &FxHashSet<TypeId>) -> bool {
+		let providers = self.providers.lock().unwrap();
+		types.iter().any(|type_id| {
+			providers
+				.iter()
+				.rev()
+				.find(|provided| provided.type_id == *type_id)
+				.is_some_and(|provided| provided.changed)
+		})
+	}
+	pub(crate) fn push_provider<T: Send + Sync + 'static>(&self, value: Arc<T>, changed: bool) {
+		let mut providers = self.providers.lock().unwrap();
+		providers.push(ProvidedValue {
+			type_id: TypeId::of::<T>(),
+			value,
+			changed,
+		});
+	}
+	pub(crate) fn pop_provider(&self) {
+		self.providers.lock().unwrap().pop();
+	}
+
+	/// Start collecting the provided types read until the matching [`Context::finish_consuming`].
+	pub(crate) fn start_consuming(&self) {
+		self.consumed.lock().unwrap().push(FxHashSet::default());
+	}
+	pub(crate) fn finish_consuming(&self) -> FxHashSet<TypeId> {
+		self.consumed.lock().unwrap().pop().unwrap_or_default()
+	}
+	/// Count `types` as read, e.g. by a memoized subtree that got reused without reading them again
+	pub(crate) fn consume_all(&self, types: &FxHashSet<TypeId>) {
+		for consumed in self.consumed.lock().unwrap().iter_mut() {
+			consumed.extend(types);
+		}
+	}
+	fn consume(&self, type_id: TypeId) {
+		// outer memos depend on everything their inner ones do
+		for consumed in self.consumed.lock().unwrap().iter_mut() {
+			consumed.insert(type_id);
+		}
+	}
+}
+
+/// The other end of a headless context's connections.
+///
+/// Nothing ever reads the fusion socket, so elements can create and update real nodes
+/// but anything waiting on the server (e.g. a method call) never gets an answer.
+#[cfg(any(test, feature = "headless"))]
+struct HeadlessServer {
+	root: SpatialRef,
+	/// Holds the messages sent by nodes, dropping it would make every node creation fail
+	_client: Mutex<Client>,
+	_socket: UnixStream,
+	_dbus: Connection,
+}
+#[cfg(any(test, feature = "headless"))]
+impl HeadlessServer {
+	fn new(dbus: Connection) -> std::io::Result<Self> {
+		let (client_stream, socket) = UnixStream::pair()?;
+		let client = Client::from_connection(client_stream);
+		Ok(HeadlessServer {
+			root: client.get_root().clone().as_spatial_ref(),
+			_client: Mutex::new(client),
+			_socket: socket,
+			_dbus: dbus,
+		})
+	}
 }
@@ -3,7 +3,7 @@ use crate::context::Context;
 use crate::element::ElementWrapper;
 pub use derive_setters;
 use stardust_xr_fusion::root::FrameInfo;
-use stardust_xr_fusion::spatial::{SpatialAspect, SpatialRef, Transform};
+use stardust_xr_fusion::spatial::{BoundingBox, SpatialAspect, SpatialRef, Transform};
 use std::any::Any;
 use std::fmt::Debug;
 use std::path::Path;
@@ -13,10 +13,12 @@ pub struct CreateInnerInfo<'a> {
 	pub element_path: &'a Path,
 }
 
-pub trait CustomElement<State: ValidState>: Any + Debug + Send + Sync + Sized + 'static {
+pub trait CustomElement<State: ValidState>:
+	LocalTransform + Any + Debug + Send + Sync + Sized + 'static
+{
 	/// The imperative struct containing non-saved state
 	type Inner: Send + Sync + 'static;
-	/// A global shared across the whole View
+	/// Shared by every element of this type in the same `ResourceScope`, dropped when the last one is destroyed
 	type Resource: Default + Send + Sync + 'static;
 	/// Error type for the element
 	type Error: ToString;
@@ -29,7 +31,17 @@ pub trait CustomElement<State: ValidState>: Any + Debug + Send + Sync + Sized +
 	) -> Result<Self::Inner, Self::Error>;
 	/// Update the inner imperative struct with the new state of the node.
 	/// You will need to check for changes between `self` and `old_self` and update accordingly.
-	fn diff(&self, old_self: &Self, inner: &mut Self::Inner, resource: &mut Self::Resource);
+	fn diff(
+		&self,
+		context: &Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		resource: &mut Self::Resource,
+	);
+	/// If a change can't be applied to the existing inner, return true to destroy and recreate it (and its children) instead of diffing.
+	fn needs_recreate(&self, _old_self: &Self) -> bool {
+		false
+	}
 	/// Every frame on the server
 	fn frame(
 		&self,
@@ -41,6 +53,10 @@ pub trait CustomElement<State: ValidState>: Any + Debug + Send + Sync + Sized +
 	}
 	/// Return the SpatialRef that all child elements should be parented under.
 	fn spatial_aspect(&self, inner: &Self::Inner) -> SpatialRef;
+	/// Estimated bounds of this element in its own space, not counting children. Used by layout elements like `Stack`.
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		None
+	}
 	/// Call this to add the element as a child of another one.
 	fn build(self) -> ElementWrapper<State, Self, ()> {
 		ElementWrapper::<State, Self, ()>::new(self)
@@ -84,3 +100,18 @@ pub trait Transformable: Sized {
 		self
 	}
 }
+
+/// The local transform of an element, if it has one. Used to record headless scenes and measure bounds.
+///
+/// Comes from [`Transformable`] for elements that implement it,
+/// any other custom element just needs an empty `impl LocalTransform for MyElement {}`.
+pub trait LocalTransform {
+	fn local_transform(&self) -> Option<Transform> {
+		None
+	}
+}
+impl<T: Transformable> LocalTransform for T {
+	fn local_transform(&self) -> Option<Transform> {
+		Some(*self.transform())
+	}
+}
@@ -1,8 +1,11 @@
 use crate::{
-	Context, Element, ValidState, element::ElementDiffer, inner::ElementInnerMap,
+	Context, Element, ValidState,
+	blueprint::BlueprintNode,
+	element::{ElementDiffer, ParentSpace},
+	inner::ElementInnerMap,
 	resource::ResourceRegistry,
 };
-use stardust_xr_fusion::{root::FrameInfo, spatial::SpatialRef};
+use stardust_xr_fusion::{root::FrameInfo, spatial::BoundingBox};
 use std::path::Path;
 
 /// Trait for elements that support dynamic type swapping (rare cases like KDL environments)
@@ -12,7 +15,7 @@ pub(crate) trait DynamicDiffer<State: ValidState>: Send + Sync + std::any::Any {
 		&self,
 		inner_key: u64,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -34,7 +37,7 @@ pub(crate) trait DynamicDiffer<State: ValidState>: Send + Sync + std::any::Any {
 		inner_key: u64,
 		old: &dyn DynamicDiffer<State>,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -42,6 +45,10 @@ pub(crate) trait DynamicDiffer<State: ValidState>: Send + Sync + std::any::Any {
 
 	/// Clean up this element and all children
 	fn dynamic_destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap);
+
+	fn dynamic_bounds_hint(&self) -> Option<BoundingBox>;
+
+	fn dynamic_blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>);
 }
 
 // Blanket implementation for any ElementDiffer + Any
@@ -53,7 +60,7 @@ where
 		&self,
 		inner_key: u64,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -84,7 +91,7 @@ where
 		inner_key: u64,
 		old: &dyn DynamicDiffer<State>,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -119,6 +126,14 @@ where
 	fn dynamic_destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
 		ElementDiffer::destroy_inner_recursive(self, inner_map)
 	}
+
+	fn dynamic_bounds_hint(&self) -> Option<BoundingBox> {
+		ElementDiffer::bounds_hint(self)
+	}
+
+	fn dynamic_blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		ElementDiffer::blueprint(self, context, nodes)
+	}
 }
 
 pub struct DynamicElement<State: ValidState>(Box<dyn DynamicDiffer<State> + Send + Sync>);
@@ -132,7 +147,7 @@ impl<State: ValidState> ElementDiffer<State> for DynamicElement<State> {
 		&self,
 		inner_key: u64,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -163,7 +178,7 @@ impl<State: ValidState> ElementDiffer<State> for DynamicElement<State> {
 		inner_key: u64,
 		old: &Self,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -183,5 +198,13 @@ impl<State: ValidState> ElementDiffer<State> for DynamicElement<State> {
 	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
 		self.0.dynamic_destroy_inner_recursive(inner_map)
 	}
+
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		self.0.dynamic_bounds_hint()
+	}
+
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		self.0.dynamic_blueprint(context, nodes)
+	}
 }
 impl<State: ValidState> Element<State> for DynamicElement<State> {}
@@ -2,22 +2,29 @@
 
 use crate::{
 	Context, CreateInnerInfo, CustomElement, ValidState,
+	blueprint::BlueprintNode,
 	dynamic_element::{DynamicDiffer, DynamicElement},
-	inner::ElementInnerMap,
+	element_ref::ElementRef,
+	inner::{ElementInnerMap, StateHook},
 	mapped::Mapped,
 	resource::ResourceRegistry,
+	scene::SceneNode,
+};
+use glam::Vec3;
+use rustc_hash::{FxHashMap, FxHashSet};
+use stardust_xr_fusion::{
+	root::FrameInfo,
+	spatial::{BoundingBox, SpatialRef, Transform},
 };
-use rustc_hash::FxHashMap;
-use stardust_xr_fusion::{root::FrameInfo, spatial::SpatialRef};
 use std::{
 	any::TypeId,
 	hash::{DefaultHasher, Hash, Hasher},
 	marker::PhantomData,
 	path::{Path, PathBuf},
-	sync::OnceLock,
+	sync::{Arc, OnceLock},
 };
 
-fn element_type_name<E: std::any::Any>() -> &'static str {
+pub(crate) fn element_type_name<E: std::any::Any>() -> &'static str {
 	let type_name = std::any::type_name::<E>();
 	// Cut off generics first
 	let no_generics = type_name.find('<').map_or(type_name, |i| &type_name[..i]);
@@ -50,6 +57,9 @@ pub fn hash_inner_key<T: 'static, H: Hash>(parent_key: u64, local: &H) -> u64 {
 	hasher.finish()
 }
 
+/// The space an element's inner gets parented under, on the server or a headless context's fake one.
+pub(crate) type ParentSpace = SpatialRef;
+
 pub trait Element<State: ValidState>: ElementDiffer<State> + Sized + 'static {
 	fn map<
 		SuperState: ValidState,
@@ -76,7 +86,7 @@ pub(crate) trait ElementDiffer<State: ValidState>:
 		&self,
 		inner_key: u64,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -98,7 +108,7 @@ pub(crate) trait ElementDiffer<State: ValidState>:
 		inner_key: u64,
 		old: &Self,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -106,6 +116,40 @@ pub(crate) trait ElementDiffer<State: ValidState>:
 
 	/// Clean up this element and all children
 	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap);
+
+	/// Estimated local bounds of this element and its children, used for layout
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		None
+	}
+
+	/// Describe this element and its children for snapshots, see [`crate::Blueprint`]
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>);
+}
+
+/// Smallest box containing both
+pub(crate) fn union_bounds(a: Option<BoundingBox>, b: Option<BoundingBox>) -> Option<BoundingBox> {
+	match (a, b) {
+		(Some(a), Some(b)) => {
+			let (a_center, a_half) = (Vec3::from(a.center), Vec3::from(a.size) / 2.0);
+			let (b_center, b_half) = (Vec3::from(b.center), Vec3::from(b.size) / 2.0);
+			let min = (a_center - a_half).min(b_center - b_half);
+			let max = (a_center + a_half).max(b_center + b_half);
+			Some(BoundingBox {
+				center: ((min + max) / 2.0).into(),
+				size: (max - min).into(),
+			})
+		}
+		(a, b) => a.or(b),
+	}
+}
+/// Move bounds into the parent's space, ignoring rotation
+pub(crate) fn transform_bounds(bounds: BoundingBox, transform: &Transform) -> BoundingBox {
+	let scale = transform.scale.map(Vec3::from).unwrap_or(Vec3::ONE);
+	let translation = transform.translation.map(Vec3::from).unwrap_or(Vec3::ZERO);
+	BoundingBox {
+		center: (Vec3::from(bounds.center) * scale + translation).into(),
+		size: (Vec3::from(bounds.size) * scale.abs()).into(),
+	}
 }
 
 // HeapElement is not needed in the zero-cost abstraction approach
@@ -117,7 +161,7 @@ impl<State: ValidState> ElementDiffer<State> for () {
 		&self,
 		_inner_key: u64,
 		_context: &Context,
-		_parent_space: &SpatialRef,
+		_parent_space: &ParentSpace,
 		_element_path: &Path,
 		_inner_map: &mut ElementInnerMap,
 		_resources: &mut ResourceRegistry,
@@ -136,7 +180,7 @@ impl<State: ValidState> ElementDiffer<State> for () {
 		_inner_key: u64,
 		_old: &Self,
 		_context: &Context,
-		_parent_space: &SpatialRef,
+		_parent_space: &ParentSpace,
 		_element_path: &Path,
 		_inner_map: &mut ElementInnerMap,
 		_resources: &mut ResourceRegistry,
@@ -144,6 +188,7 @@ impl<State: ValidState> ElementDiffer<State> for () {
 		// Empty tuple - nothing to diff
 	}
 	fn destroy_inner_recursive(&self, _inner_map: &mut ElementInnerMap) {}
+	fn blueprint(&self, _context: &Context, _nodes: &mut Vec<BlueprintNode>) {}
 }
 
 // For 2-tuples (the main case when adding children)
@@ -154,7 +199,7 @@ impl<State: ValidState, A: ElementDiffer<State>, B: ElementDiffer<State>> Elemen
 		&self,
 		inner_key: u64,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -194,7 +239,7 @@ impl<State: ValidState, A: ElementDiffer<State>, B: ElementDiffer<State>> Elemen
 		inner_key: u64,
 		old: &Self,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -225,6 +270,13 @@ impl<State: ValidState, A: ElementDiffer<State>, B: ElementDiffer<State>> Elemen
 		self.0.destroy_inner_recursive(inner_map);
 		self.1.destroy_inner_recursive(inner_map);
 	}
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		union_bounds(self.0.bounds_hint(), self.1.bounds_hint())
+	}
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		self.0.blueprint(context, nodes);
+		self.1.blueprint(context, nodes);
+	}
 }
 
 // Vec<Element> implementation - simple positional diffing
@@ -233,7 +285,7 @@ impl<State: ValidState, E: Element<State>> ElementDiffer<State> for Vec<E> {
 		&self,
 		inner_key: u64,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -268,7 +320,7 @@ impl<State: ValidState, E: Element<State>> ElementDiffer<State> for Vec<E> {
 		inner_key: u64,
 		old: &Self,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -313,6 +365,16 @@ impl<State: ValidState, E: Element<State>> ElementDiffer<State> for Vec<E> {
 			element.destroy_inner_recursive(inner_map);
 		}
 	}
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		self.iter()
+			.map(|element| element.bounds_hint())
+			.fold(None, union_bounds)
+	}
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		for element in self {
+			element.blueprint(context, nodes);
+		}
+	}
 }
 
 // HashMap<K, Element> implementation - stable key diffing
@@ -323,7 +385,7 @@ impl<State: ValidState, K: Hash + Eq + Clone + Send + Sync + 'static, E: Element
 		&self,
 		inner_key: u64,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -357,7 +419,7 @@ impl<State: ValidState, K: Hash + Eq + Clone + Send + Sync + 'static, E: Element
 		inner_key: u64,
 		old: &Self,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -406,6 +468,153 @@ impl<State: ValidState, K: Hash + Eq + Clone + Send + Sync + 'static, E: Element
 			element.destroy_inner_recursive(inner_map);
 		}
 	}
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		self.values()
+			.map(|element| element.bounds_hint())
+			.fold(None, union_bounds)
+	}
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		for element in self.values() {
+			element.blueprint(context, nodes);
+		}
+	}
+}
+
+/// Children with a stable identity per key that still keep their order.
+///
+/// Each child's inner is keyed by its key rather than its position, so inserting, removing or
+/// reordering children only creates/destroys the ones whose key appeared/disappeared while the
+/// rest keep their inner (grab poses, input actions, momentum...) untouched.
+pub struct KeyedChildren<K, E> {
+	children: Vec<(K, E)>,
+}
+impl<K: Hash + Eq, E: 'static> FromIterator<(K, E)> for KeyedChildren<K, E> {
+	fn from_iter<T: IntoIterator<Item = (K, E)>>(iter: T) -> Self {
+		let children = iter.into_iter().collect::<Vec<_>>();
+		// compare the keys themselves, a collision of their hashes shouldn't drop a real child
+		let mut seen = FxHashSet::default();
+		let keep = children
+			.iter()
+			.map(|(key, _)| seen.insert(key))
+			.collect::<Vec<_>>();
+		let children = children
+			.into_iter()
+			.zip(keep)
+			.filter_map(|(child, keep)| {
+				if !keep {
+					// 2 children with the same key would fight over the same inner
+					tracing::warn!(
+						"Duplicate key in keyed children of {}, skipping it",
+						element_type_name::<E>()
+					);
+				}
+				keep.then_some(child)
+			})
+			.collect();
+		KeyedChildren { children }
+	}
+}
+impl<State: ValidState, K: Hash + Eq + Clone + Send + Sync + 'static, E: Element<State>>
+	ElementDiffer<State> for KeyedChildren<K, E>
+{
+	fn create_inner_recursive(
+		&self,
+		inner_key: u64,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		for (key, element) in &self.children {
+			element.create_inner_recursive(
+				hash_inner_key::<E, K>(inner_key, key),
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			);
+		}
+	}
+
+	fn frame_recursive(
+		&self,
+		context: &Context,
+		info: &FrameInfo,
+		state: &mut State,
+		inner_map: &mut ElementInnerMap,
+	) {
+		for (_, element) in &self.children {
+			element.frame_recursive(context, info, state, inner_map);
+		}
+	}
+
+	fn diff_same_type(
+		&self,
+		inner_key: u64,
+		old: &Self,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let old_children: FxHashMap<&K, &E> = old
+			.children
+			.iter()
+			.map(|(key, element)| (key, element))
+			.collect();
+
+		// Walk the new order: moved children keep their inner, only new keys get created
+		for (key, new_elem) in &self.children {
+			let child_key = hash_inner_key::<E, K>(inner_key, key);
+			match old_children.get(key) {
+				Some(old_elem) => new_elem.diff_same_type(
+					child_key,
+					old_elem,
+					context,
+					parent_space,
+					element_path,
+					inner_map,
+					resources,
+				),
+				None => new_elem.create_inner_recursive(
+					child_key,
+					context,
+					parent_space,
+					element_path,
+					inner_map,
+					resources,
+				),
+			}
+		}
+
+		// Destroy children whose key is gone
+		let new_keys: FxHashSet<&K> = self.children.iter().map(|(key, _)| key).collect();
+		for (key, old_elem) in &old.children {
+			if !new_keys.contains(key) {
+				old_elem.destroy_inner_recursive(inner_map);
+			}
+		}
+	}
+
+	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
+		for (_, element) in &self.children {
+			element.destroy_inner_recursive(inner_map);
+		}
+	}
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		self.children
+			.iter()
+			.map(|(_, element)| element.bounds_hint())
+			.fold(None, union_bounds)
+	}
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		for (_, element) in &self.children {
+			element.blueprint(context, nodes);
+		}
+	}
 }
 
 // Option<Element> implementation
@@ -414,7 +623,7 @@ impl<State: ValidState, E: Element<State>> ElementDiffer<State> for Option<E> {
 		&self,
 		inner_key: u64,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -447,7 +656,7 @@ impl<State: ValidState, E: Element<State>> ElementDiffer<State> for Option<E> {
 		inner_key: u64,
 		old: &Self,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
@@ -490,11 +699,111 @@ impl<State: ValidState, E: Element<State>> ElementDiffer<State> for Option<E> {
 			element.destroy_inner_recursive(inner_map);
 		}
 	}
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		self.as_ref()?.bounds_hint()
+	}
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		if let Some(element) = self {
+			element.blueprint(context, nodes);
+		}
+	}
+}
+
+/// A subtree that's no longer in the blueprint but stays alive until its exit transition is done.
+pub(crate) trait Leaving: Send + Sync + std::any::Any {
+	/// Key the subtree was created at
+	fn inner_key(&self) -> u64;
+	/// Advance the exit transition by `delta` seconds
+	fn tick(&mut self, delta: f32);
+	fn finished(&self) -> bool;
+	/// Rebuild and diff the subtree at its current progress
+	fn rebuild(
+		&mut self,
+		context: &Context,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	);
+	fn destroy(&self, inner_map: &mut ElementInnerMap);
+	fn as_any(&self) -> &dyn std::any::Any;
+	fn into_any(self: Box<Self>) -> Box<dyn std::any::Any>;
+}
+impl std::fmt::Debug for dyn Leaving {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Leaving")
+			.field("inner_key", &self.inner_key())
+			.finish()
+	}
+}
+/// Step every leaving subtree after a diff, destroying the ones that are done
+pub(crate) fn update_leaving(
+	context: &Context,
+	inner_map: &mut ElementInnerMap,
+	resources: &mut ResourceRegistry,
+) {
+	for mut leaving in inner_map.take_all_leaving() {
+		if leaving.finished() {
+			let instant_destroy = inner_map.set_instant_destroy(true);
+			leaving.destroy(inner_map);
+			inner_map.set_instant_destroy(instant_destroy);
+		} else {
+			leaving.rebuild(context, inner_map, resources);
+			inner_map.push_leaving(leaving);
+		}
+	}
+}
+
+/// A failure to create an element's inner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementError {
+	/// Element name without the namespace or generics, e.g. `Model`
+	pub element_type: &'static str,
+	/// Unique path of the element that failed
+	pub element_path: PathBuf,
+	/// The element's error, converted with `ToString`
+	pub message: String,
+}
+impl std::fmt::Display for ElementError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"Failed to create {} at {}: {}",
+			self.element_type,
+			self.element_path.display(),
+			self.message
+		)
+	}
+}
+impl std::error::Error for ElementError {}
+
+type LifecycleHook<State> = Arc<dyn Fn(&mut State) + Send + Sync>;
+/// Callbacks run on the frame after the element's inner gets created, changed or destroyed
+struct Lifecycle<State: ValidState, E> {
+	on_mount: Option<LifecycleHook<State>>,
+	on_update: Option<(LifecycleHook<State>, fn(&E, &E) -> bool)>,
+	on_unmount: Option<LifecycleHook<State>>,
+}
+impl<State: ValidState, E> Default for Lifecycle<State, E> {
+	fn default() -> Self {
+		Lifecycle {
+			on_mount: None,
+			on_update: None,
+			on_unmount: None,
+		}
+	}
+}
+fn queue_lifecycle_hook<State: ValidState>(
+	hook: &LifecycleHook<State>,
+	inner_map: &mut ElementInnerMap,
+) {
+	let hook = hook.clone();
+	inner_map.queue_hook(StateHook::new(move |state: &mut State| hook(state)));
 }
 
 pub struct ElementWrapper<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>> {
 	pub custom_element: Option<E>,
 	children: C,
+	element_ref: Option<ElementRef>,
+	lifecycle: Lifecycle<State, E>,
 	inner_key: OnceLock<u64>,
 	state_phantom: PhantomData<State>,
 }
@@ -506,14 +815,32 @@ impl<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>>
 		ElementWrapper {
 			custom_element: Some(custom_element),
 			children: (),
+			element_ref: None,
+			lifecycle: Lifecycle::default(),
 			inner_key: OnceLock::new(),
 			state_phantom: PhantomData,
 		}
 	}
+	/// Swap out the children wholesale, for layouts that build their own children structure
+	pub(crate) fn replace_children<NC: ElementDiffer<State>>(
+		self,
+		children: NC,
+	) -> ElementWrapper<State, E, NC> {
+		ElementWrapper {
+			custom_element: self.custom_element,
+			children,
+			element_ref: self.element_ref,
+			lifecycle: self.lifecycle,
+			inner_key: self.inner_key,
+			state_phantom: PhantomData,
+		}
+	}
 	pub fn child<NC: Element<State>>(self, child: NC) -> ElementWrapper<State, E, (C, NC)> {
 		ElementWrapper {
 			custom_element: self.custom_element,
 			children: (self.children, child),
+			element_ref: self.element_ref,
+			lifecycle: self.lifecycle,
 			inner_key: self.inner_key,
 			state_phantom: PhantomData,
 		}
@@ -525,6 +852,8 @@ impl<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>>
 		ElementWrapper {
 			custom_element: self.custom_element,
 			children: (self.children, child),
+			element_ref: self.element_ref,
+			lifecycle: self.lifecycle,
 			inner_key: self.inner_key,
 			state_phantom: PhantomData,
 		}
@@ -536,21 +865,89 @@ impl<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>>
 		ElementWrapper {
 			custom_element: self.custom_element,
 			children: (self.children, children.into_iter().collect()),
+			element_ref: self.element_ref,
+			lifecycle: self.lifecycle,
 			inner_key: self.inner_key,
 			state_phantom: PhantomData,
 		}
 	}
+	/// Add children identified by a key instead of their position, in no particular order.
 	pub fn stable_children<NC: Element<State>, K: Eq + Hash + Clone + Send + Sync + 'static>(
 		self,
 		children: impl IntoIterator<Item = (K, NC)>,
 	) -> ElementWrapper<State, E, (C, FxHashMap<K, NC>)> {
 		ElementWrapper {
 			custom_element: self.custom_element,
-			children: (self.children, FxHashMap::from_iter(children)),
+			children: (self.children, children.into_iter().collect()),
+			element_ref: self.element_ref,
+			lifecycle: self.lifecycle,
 			inner_key: self.inner_key,
 			state_phantom: PhantomData,
 		}
 	}
+	/// Add children identified by a key that keep their order, see [`KeyedChildren`].
+	pub fn keyed_children<NC: Element<State>, K: Eq + Hash + Clone + Send + Sync + 'static>(
+		self,
+		children: impl IntoIterator<Item = (K, NC)>,
+	) -> ElementWrapper<State, E, (C, KeyedChildren<K, NC>)> {
+		ElementWrapper {
+			custom_element: self.custom_element,
+			children: (self.children, children.into_iter().collect()),
+			element_ref: self.element_ref,
+			lifecycle: self.lifecycle,
+			inner_key: self.inner_key,
+			state_phantom: PhantomData,
+		}
+	}
+	/// Attach `element_ref` to this element so state callbacks can get at it, see [`ElementRef`].
+	pub fn with_ref(mut self, element_ref: ElementRef) -> Self {
+		self.element_ref = Some(element_ref);
+		self
+	}
+	/// Called on the next frame after this element gets created.
+	pub fn on_mount(mut self, on_mount: impl Fn(&mut State) + Send + Sync + 'static) -> Self {
+		self.lifecycle.on_mount = Some(Arc::new(on_mount));
+		self
+	}
+	/// Called on the next frame after this element gets destroyed.
+	pub fn on_unmount(mut self, on_unmount: impl Fn(&mut State) + Send + Sync + 'static) -> Self {
+		self.lifecycle.on_unmount = Some(Arc::new(on_unmount));
+		self
+	}
+}
+impl<State: ValidState, E: CustomElement<State> + PartialEq, C: ElementDiffer<State>>
+	ElementWrapper<State, E, C>
+{
+	/// Called on the next frame after this element gets diffed against a different value of itself.
+	pub fn on_update(mut self, on_update: impl Fn(&mut State) + Send + Sync + 'static) -> Self {
+		self.lifecycle.on_update = Some((Arc::new(on_update), |new, old| new != old));
+		self
+	}
+}
+impl<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>>
+	ElementWrapper<State, E, C>
+{
+	fn attach_ref(&self, inner_key: u64, element_path: &Path, inner_map: &ElementInnerMap) {
+		let (Some(element_ref), Some(element)) = (&self.element_ref, &self.custom_element) else {
+			return;
+		};
+		let spatial = inner_map
+			.get::<State, E>(inner_key)
+			.map(|inner| element.spatial_aspect(inner));
+		element_ref.attach(inner_key, spatial, element_path);
+	}
+	/// The space children should be parented under, falling back to our parent's if we have no inner
+	fn child_parent_space(
+		&self,
+		inner_key: u64,
+		parent_space: &ParentSpace,
+		inner_map: &ElementInnerMap,
+	) -> ParentSpace {
+		match (&self.custom_element, inner_map.get::<State, E>(inner_key)) {
+			(Some(element), Some(inner)) => element.spatial_aspect(inner),
+			_ => parent_space.clone(),
+		}
+	}
 }
 impl<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>> ElementDiffer<State>
 	for ElementWrapper<State, E, C>
@@ -559,44 +956,64 @@ impl<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>> Elemen
 		&self,
 		inner_key: u64,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
 	) {
-		let element_path = join_element_path::<E>(element_path, inner_key);
+		let parent_path = element_path;
+		let element_path = join_element_path::<E>(parent_path, inner_key);
 
 		// Store the inner key for later use in frame/destroy
 		let _ = self.inner_key.set(inner_key);
 
 		// Create this element's inner
 		if let Some(element) = &self.custom_element {
+			let resource_key = resources.acquire::<State, E>();
 			let result = element.create_inner(
 				context,
 				CreateInnerInfo {
 					parent_space,
 					element_path: &element_path,
 				},
-				resources.get::<State, E>(),
+				resources.get::<State, E>(resource_key),
 			);
-
-			if let Ok(inner) = result {
-				inner_map.insert::<State, E>(inner_key, inner);
+			let created = match result {
+				Ok(inner) => {
+					inner_map.insert::<State, E>(inner_key, inner);
+					inner_map.set_resource_key(inner_key, resource_key);
+					true
+				}
+				Err(error) => {
+					resources.release([resource_key]);
+					let error = ElementError {
+						element_type: element_type_name::<E>(),
+						element_path: element_path.clone(),
+						message: error.to_string(),
+					};
+					tracing::error!("{error}");
+					inner_map.push_error(error);
+					false
+				}
+			};
+			// headless and inspected projectors keep track of the tree
+			if let Some(scene) = inner_map.scene_mut().filter(|_| created) {
+				scene.insert(
+					inner_key,
+					SceneNode::new::<State, E>(element, inner_key, &element_path, parent_path),
+				);
 			}
 		}
 
-		// Get spatial ref for children
-		let child_parent_space = if let Some(element) = &self.custom_element {
-			if let Some(inner) = inner_map.get::<State, E>(inner_key) {
-				element.spatial_aspect(inner)
-			} else {
-				parent_space.clone()
+		self.attach_ref(inner_key, &element_path, inner_map);
+		if let Some(on_mount) = &self.lifecycle.on_mount {
+			if inner_map.contains(inner_key) {
+				queue_lifecycle_hook(on_mount, inner_map);
 			}
-		} else {
-			parent_space.clone()
-		};
+		}
 
 		// Create children
+		let child_parent_space = self.child_parent_space(inner_key, parent_space, inner_map);
 		self.children.create_inner_recursive(
 			inner_key,
 			context,
@@ -633,21 +1050,56 @@ impl<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>> Elemen
 		inner_key: u64,
 		old: &Self,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
 	) {
-		let element_path = join_element_path::<E>(element_path, inner_key);
-
 		// Store the inner key for later use in frame/destroy
 		let _ = self.inner_key.set(inner_key);
+		if old.element_ref != self.element_ref {
+			if let Some(old_ref) = &old.element_ref {
+				old_ref.detach(inner_key);
+			}
+		}
 
 		// Diff this element
 		match (&self.custom_element, &old.custom_element) {
+			(Some(new_element), Some(old_element)) if new_element.needs_recreate(old_element) => {
+				ElementDiffer::destroy_inner_recursive(old, inner_map);
+				ElementDiffer::create_inner_recursive(
+					self,
+					inner_key,
+					context,
+					parent_space,
+					element_path,
+					inner_map,
+					resources,
+				);
+				return; // Don't diff children since we just recreated everything
+			}
 			(Some(new_element), Some(old_element)) => {
-				if let Some(inner) = inner_map.get_mut::<State, E>(inner_key) {
-					new_element.diff(old_element, inner, resources.get::<State, E>());
+				let resource_key = inner_map.resource_key(inner_key);
+				if let (Some(inner), Some(resource_key)) =
+					(inner_map.get_mut::<State, E>(inner_key), resource_key)
+				{
+					new_element.diff(
+						context,
+						old_element,
+						inner,
+						resources.get::<State, E>(resource_key),
+					);
+				}
+				if let Some((on_update, changed)) = &self.lifecycle.on_update {
+					if inner_map.contains(inner_key) && changed(new_element, old_element) {
+						queue_lifecycle_hook(on_update, inner_map);
+					}
+				}
+				if let Some(node) = inner_map
+					.scene_mut()
+					.and_then(|scene| scene.get_mut(inner_key))
+				{
+					node.update::<State, E>(new_element);
 				}
 			}
 			(Some(_), None) => {
@@ -657,7 +1109,7 @@ impl<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>> Elemen
 					inner_key,
 					context,
 					parent_space,
-					&element_path,
+					element_path,
 					inner_map,
 					resources,
 				);
@@ -673,18 +1125,11 @@ impl<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>> Elemen
 			}
 		}
 
-		// Get spatial ref for children
-		let child_parent_space = if let Some(element) = &self.custom_element {
-			if let Some(inner) = inner_map.get::<State, E>(inner_key) {
-				element.spatial_aspect(inner)
-			} else {
-				parent_space.clone()
-			}
-		} else {
-			parent_space.clone()
-		};
+		let element_path = join_element_path::<E>(element_path, inner_key);
+		self.attach_ref(inner_key, &element_path, inner_map);
 
 		// Diff children
+		let child_parent_space = self.child_parent_space(inner_key, parent_space, inner_map);
 		self.children.diff_same_type(
 			inner_key,
 			&old.children,
@@ -697,17 +1142,157 @@ impl<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>> Elemen
 	}
 
 	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
-		// Destroy children first
+		// Destroy children first, they go away with our inner so they can't play exit transitions
+		let instant_destroy = inner_map
+			.set_instant_destroy(inner_map.instant_destroy() || self.custom_element.is_some());
 		self.children.destroy_inner_recursive(inner_map);
+		inner_map.set_instant_destroy(instant_destroy);
 
 		// Destroy this element using the stored inner key
 		if let Some(&inner_key) = self.inner_key.get() {
+			if let Some(on_unmount) = &self.lifecycle.on_unmount {
+				if inner_map.contains(inner_key) {
+					queue_lifecycle_hook(on_unmount, inner_map);
+				}
+			}
 			inner_map.remove(inner_key);
+			if let Some(element_ref) = &self.element_ref {
+				element_ref.detach(inner_key);
+			}
 		}
 	}
+
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		let Some(element) = &self.custom_element else {
+			return self.children.bounds_hint();
+		};
+		let local_bounds = union_bounds(element.bounds_hint(), self.children.bounds_hint())?;
+		Some(match element.local_transform() {
+			Some(transform) => transform_bounds(local_bounds, &transform),
+			None => local_bounds,
+		})
+	}
+
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		let Some(element) = &self.custom_element else {
+			return self.children.blueprint(context, nodes);
+		};
+		let mut children = Vec::new();
+		self.children.blueprint(context, &mut children);
+		nodes.push(BlueprintNode {
+			element_type: element_type_name::<E>().to_string(),
+			debug: format!("{element:?}"),
+			children,
+		});
+	}
 }
 
 impl<State: ValidState, E: CustomElement<State>, C: ElementDiffer<State>> Element<State>
 	for ElementWrapper<State, E, C>
 {
 }
+
+#[tokio::test]
+async fn asteroids_keyed_children() {
+	use crate::{
+		Projector, Reify,
+		elements::{Spatial, Text},
+	};
+
+	struct TestState {
+		items: Vec<&'static str>,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Spatial::default().build().keyed_children(
+				self.items
+					.iter()
+					.map(|item| (*item, Text::new(item).build())),
+			)
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let mut state = TestState {
+		items: vec!["a", "b", "c"],
+	};
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	let paths: FxHashMap<String, PathBuf> = projector
+		.scene()
+		.unwrap()
+		.find("Text")
+		.map(|node| (node.debug.clone(), node.path.clone()))
+		.collect();
+
+	// reorder and drop the first item, every remaining text should keep its node
+	state.items = vec!["c", "a"];
+	projector.update(&context, &mut state);
+
+	let scene = projector.scene().unwrap();
+	assert_eq!(scene.find("Text").count(), 2);
+	for node in scene.find("Text") {
+		assert_eq!(paths[&node.debug], node.path);
+	}
+
+	// only the repeated key gets skipped
+	let keyed = KeyedChildren::from_iter([(1, "a"), (2, "b"), (1, "c")]);
+	let keys = keyed
+		.children
+		.iter()
+		.map(|(key, _)| *key)
+		.collect::<Vec<_>>();
+	assert_eq!(keys, [1, 2]);
+}
+
+#[tokio::test]
+async fn asteroids_lifecycle_hooks() {
+	use crate::{
+		Projector, Reify, Transformable,
+		elements::{Spatial, Text},
+	};
+
+	#[derive(Default)]
+	struct TestState {
+		show_text: bool,
+		offset: f32,
+		events: Vec<&'static str>,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Spatial::default()
+				.pos([self.offset, 0.0, 0.0])
+				.build()
+				.on_update(|state: &mut Self| state.events.push("moved"))
+				.maybe_child(self.show_text.then(|| {
+					Text::new("hi")
+						.build()
+						.on_mount(|state: &mut Self| state.events.push("mounted"))
+						.on_unmount(|state: &mut Self| state.events.push("unmounted"))
+				}))
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let info = FrameInfo {
+		delta: 0.0,
+		elapsed: 0.0,
+	};
+	let mut state = TestState {
+		show_text: true,
+		..Default::default()
+	};
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	projector.frame(&context, &info, &mut state);
+	assert_eq!(state.events, ["mounted"]);
+
+	// diffing with the same value isn't an update
+	projector.update(&context, &mut state);
+	projector.frame(&context, &info, &mut state);
+	assert_eq!(state.events, ["mounted"]);
+
+	state.offset = 1.0;
+	state.show_text = false;
+	projector.update(&context, &mut state);
+	projector.frame(&context, &info, &mut state);
+	assert_eq!(state.events, ["mounted", "moved", "unmounted"]);
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use stardust_xr_fusion::spatial::SpatialRef;
+use std::{
+	fmt::Debug,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+};
+
+struct ElementRefTarget {
+	inner_key: u64,
+	spatial: Option<SpatialRef>,
+	path: PathBuf,
+}
+
+/// A handle to a live element, attached with `ElementWrapper::with_ref` and kept in your state.
+///
+/// Clones point to the same element. It isn't saved with the state and deserializes detached.
+#[derive(Clone, Default)]
+pub struct ElementRef(Arc<Mutex<Option<ElementRefTarget>>>);
+impl ElementRef {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The spatial children of the element get parented under, `None` if not attached or its creation failed.
+	pub fn spatial(&self) -> Option<SpatialRef> {
+		self.0.lock().unwrap().as_ref()?.spatial.clone()
+	}
+	/// Unique path of the element this is attached to.
+	pub fn path(&self) -> Option<PathBuf> {
+		Some(self.0.lock().unwrap().as_ref()?.path.clone())
+	}
+	pub fn is_attached(&self) -> bool {
+		self.0.lock().unwrap().is_some()
+	}
+
+	pub(crate) fn inner_key(&self) -> Option<u64> {
+		Some(self.0.lock().unwrap().as_ref()?.inner_key)
+	}
+	pub(crate) fn attach(&self, inner_key: u64, spatial: Option<SpatialRef>, path: &Path) {
+		self.0.lock().unwrap().replace(ElementRefTarget {
+			inner_key,
+			spatial,
+			path: path.to_path_buf(),
+		});
+	}
+	/// Only detaches if this still points at `inner_key`, another element may have taken it over since
+	pub(crate) fn detach(&self, inner_key: u64) {
+		let mut target = self.0.lock().unwrap();
+		if target
+			.as_ref()
+			.is_some_and(|target| target.inner_key == inner_key)
+		{
+			target.take();
+		}
+	}
+}
+impl PartialEq for ElementRef {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.0, &other.0)
+	}
+}
+impl Debug for ElementRef {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("ElementRef").field(&self.path()).finish()
+	}
+}
+impl Serialize for ElementRef {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_unit()
+	}
+}
+impl<'de> Deserialize<'de> for ElementRef {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		<()>::deserialize(deserializer)?;
+		Ok(Self::default())
+	}
+}
+
+#[tokio::test]
+async fn asteroids_element_ref() {
+	use crate::{
+		Context, Element, Projector, Reify,
+		custom::CustomElement,
+		elements::{Spatial, Text},
+	};
+
+	struct TestState {
+		text_ref: ElementRef,
+		show_text: bool,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Spatial::default()
+				.build()
+				.maybe_child(self.show_text.then(|| {
+					Text::new("referenced")
+						.build()
+						.with_ref(self.text_ref.clone())
+				}))
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let mut state = TestState {
+		text_ref: ElementRef::new(),
+		show_text: true,
+	};
+	let mut projector = Projector::headless(&state, &context, "/".into());
+
+	let text = projector.scene().unwrap().find("Text").next().unwrap();
+	assert_eq!(state.text_ref.path().as_ref(), Some(&text.path));
+	// headless elements still have a spatial, on the fake server
+	assert!(state.text_ref.spatial().is_some());
+
+	state.show_text = false;
+	projector.update(&context, &mut state);
+	assert!(!state.text_ref.is_attached());
+}
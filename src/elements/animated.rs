@@ -0,0 +1,441 @@
+use crate::{
+	Context, CustomElement, Element, Transformable, ValidState,
+	blueprint::BlueprintNode,
+	element::{ElementDiffer, ElementWrapper, ParentSpace, gen_inner_key},
+	inner::ElementInnerMap,
+	resource::ResourceRegistry,
+};
+use glam::{Quat, Vec3};
+use stardust_xr_fusion::{
+	root::FrameInfo,
+	spatial::Transform,
+	values::{Color, color::rgba_linear},
+};
+use std::{f32::consts::PI, marker::PhantomData, path::Path, sync::OnceLock, time::Duration};
+
+/// Standard easing curves, mapping linear progress from 0 to 1 onto eased progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+	Linear,
+	QuadIn,
+	QuadOut,
+	QuadInOut,
+	CubicIn,
+	#[default]
+	CubicOut,
+	CubicInOut,
+	SineInOut,
+	/// Overshoots a little before settling
+	BackOut,
+}
+impl Easing {
+	pub fn apply(self, t: f32) -> f32 {
+		let t = t.clamp(0.0, 1.0);
+		match self {
+			Easing::Linear => t,
+			Easing::QuadIn => t * t,
+			Easing::QuadOut => 1.0 - (1.0 - t).powi(2),
+			Easing::QuadInOut => {
+				if t < 0.5 {
+					2.0 * t * t
+				} else {
+					1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+				}
+			}
+			Easing::CubicIn => t.powi(3),
+			Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+			Easing::CubicInOut => {
+				if t < 0.5 {
+					4.0 * t.powi(3)
+				} else {
+					1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+				}
+			}
+			Easing::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+			Easing::BackOut => {
+				let c1 = 1.70158;
+				let c3 = c1 + 1.0;
+				1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2)
+			}
+		}
+	}
+}
+
+/// A value that can be interpolated by [`Animated`].
+pub trait Tween: Clone + PartialEq + Send + Sync + 'static {
+	/// `t` is the eased progress, usually between 0 and 1 but may overshoot
+	fn tween(&self, to: &Self, t: f32) -> Self;
+}
+impl Tween for f32 {
+	fn tween(&self, to: &Self, t: f32) -> Self {
+		self + (to - self) * t
+	}
+}
+impl Tween for mint::Vector3<f32> {
+	fn tween(&self, to: &Self, t: f32) -> Self {
+		Vec3::from(*self).lerp(Vec3::from(*to), t).into()
+	}
+}
+impl Tween for mint::Quaternion<f32> {
+	fn tween(&self, to: &Self, t: f32) -> Self {
+		Quat::from(*self).slerp(Quat::from(*to), t).into()
+	}
+}
+impl<T: Tween> Tween for Option<T> {
+	fn tween(&self, to: &Self, t: f32) -> Self {
+		match (self, to) {
+			(Some(from), Some(to)) => Some(from.tween(to, t)),
+			_ => to.clone(),
+		}
+	}
+}
+impl Tween for Transform {
+	fn tween(&self, to: &Self, t: f32) -> Self {
+		Transform {
+			translation: self.translation.tween(&to.translation, t),
+			rotation: self.rotation.tween(&to.rotation, t),
+			scale: self.scale.tween(&to.scale, t),
+		}
+	}
+}
+impl Tween for Color {
+	fn tween(&self, to: &Self, t: f32) -> Self {
+		rgba_linear!(
+			self.c.r.tween(&to.c.r, t),
+			self.c.g.tween(&to.c.g, t),
+			self.c.b.tween(&to.c.b, t),
+			self.a.tween(&to.a, t)
+		)
+	}
+}
+impl<A: Tween, B: Tween> Tween for (A, B) {
+	fn tween(&self, to: &Self, t: f32) -> Self {
+		(self.0.tween(&to.0, t), self.1.tween(&to.1, t))
+	}
+}
+
+/// Progress of a value easing towards a target, kept in the inner map between updates
+pub(crate) struct AnimatedInner<T> {
+	from: T,
+	current: T,
+	elapsed: f32,
+}
+impl<T: Tween> AnimatedInner<T> {
+	/// Already settled at `target`, since there's nothing to animate from on creation
+	pub(crate) fn new(target: &T, duration: Duration) -> Self {
+		AnimatedInner {
+			from: target.clone(),
+			current: target.clone(),
+			elapsed: duration.as_secs_f32(),
+		}
+	}
+	/// Step towards `target`, returns false once there's nothing left to animate
+	pub(crate) fn tick(
+		&mut self,
+		delta: f32,
+		target: &T,
+		duration: Duration,
+		easing: Easing,
+	) -> bool {
+		let duration = duration.as_secs_f32();
+		if self.elapsed >= duration {
+			return false;
+		}
+		self.elapsed += delta;
+		let t = (self.elapsed / duration).min(1.0);
+		self.current = self.from.tween(target, easing.apply(t));
+		true
+	}
+	/// Start over towards `target` if it changed, returning the value to show right now
+	pub(crate) fn retarget(&mut self, old_target: &T, target: &T, duration: Duration) -> T {
+		if target != old_target {
+			// start from wherever we are right now so interrupted animations stay smooth
+			self.from = self.current.clone();
+			self.elapsed = 0.0;
+		}
+		if self.elapsed >= duration.as_secs_f32() {
+			self.current = target.clone();
+		}
+		self.current.clone()
+	}
+}
+
+/// Builds its subtree from a value that eases towards `target` over `duration` whenever `target` changes,
+/// instead of jumping to it. The in-between values never touch your state.
+///
+/// Works with anything implementing [`Tween`], e.g. a `Transform` or a `Color` for `Text` or `Lines`.
+pub struct Animated<
+	State: ValidState,
+	T: Tween,
+	E: Element<State>,
+	F: Fn(T) -> E + Send + Sync + 'static,
+> {
+	target: T,
+	duration: Duration,
+	easing: Easing,
+	builder: F,
+	subtree: OnceLock<E>,
+	inner_key: OnceLock<u64>,
+	phantom: PhantomData<State>,
+}
+impl<State: ValidState, T: Tween, E: Element<State>, F: Fn(T) -> E + Send + Sync + 'static>
+	Animated<State, T, E, F>
+{
+	pub fn new(target: T, duration: Duration, easing: Easing, builder: F) -> Self {
+		Animated {
+			target,
+			duration,
+			easing,
+			builder,
+			subtree: OnceLock::new(),
+			inner_key: OnceLock::new(),
+			phantom: PhantomData,
+		}
+	}
+
+	fn subtree_key(inner_key: u64) -> u64 {
+		gen_inner_key::<E>(inner_key, 0)
+	}
+}
+impl<State: ValidState, C: CustomElement<State> + Transformable + Clone>
+	Animated<
+		State,
+		Transform,
+		ElementWrapper<State, C, ()>,
+		Box<dyn Fn(Transform) -> ElementWrapper<State, C, ()> + Send + Sync>,
+	>
+{
+	/// Animate the transform of a single element, use [`Animated::new`] for elements with children.
+	pub fn transform(element: C, duration: Duration, easing: Easing) -> Self {
+		let target = *element.transform();
+		Animated::new(
+			target,
+			duration,
+			easing,
+			Box::new(move |transform| {
+				let mut element = element.clone();
+				*element.transform_mut() = transform;
+				element.build()
+			}),
+		)
+	}
+}
+
+impl<State: ValidState, T: Tween, E: Element<State>, F: Fn(T) -> E + Send + Sync + 'static>
+	ElementDiffer<State> for Animated<State, T, E, F>
+{
+	fn create_inner_recursive(
+		&self,
+		inner_key: u64,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let _ = self.inner_key.set(inner_key);
+		inner_map.insert_raw(inner_key, AnimatedInner::new(&self.target, self.duration));
+		let subtree = self
+			.subtree
+			.get_or_init(|| (self.builder)(self.target.clone()));
+		subtree.create_inner_recursive(
+			Self::subtree_key(inner_key),
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+	}
+
+	fn frame_recursive(
+		&self,
+		context: &Context,
+		info: &FrameInfo,
+		state: &mut State,
+		inner_map: &mut ElementInnerMap,
+	) {
+		let animating = self
+			.inner_key
+			.get()
+			.and_then(|&inner_key| inner_map.get_raw_mut::<AnimatedInner<T>>(inner_key))
+			.is_some_and(|inner| inner.tick(info.delta, &self.target, self.duration, self.easing));
+		if animating {
+			// the new value only reaches the subtree when it gets rebuilt
+			inner_map.request_update();
+		}
+
+		if let Some(subtree) = self.subtree.get() {
+			subtree.frame_recursive(context, info, state, inner_map);
+		}
+	}
+
+	fn diff_same_type(
+		&self,
+		inner_key: u64,
+		old: &Self,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let _ = self.inner_key.set(inner_key);
+		let Some(inner) = inner_map.get_raw_mut::<AnimatedInner<T>>(inner_key) else {
+			self.create_inner_recursive(
+				inner_key,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			);
+			return;
+		};
+		let current = inner.retarget(&old.target, &self.target, self.duration);
+
+		let subtree = self.subtree.get_or_init(|| (self.builder)(current));
+		match old.subtree.get() {
+			Some(old_subtree) => subtree.diff_same_type(
+				Self::subtree_key(inner_key),
+				old_subtree,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			),
+			None => subtree.create_inner_recursive(
+				Self::subtree_key(inner_key),
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			),
+		}
+	}
+
+	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
+		if let Some(subtree) = self.subtree.get() {
+			subtree.destroy_inner_recursive(inner_map);
+		}
+		if let Some(&inner_key) = self.inner_key.get() {
+			inner_map.remove(inner_key);
+		}
+	}
+
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		// settled at the target so snapshots don't depend on timing
+		(self.builder)(self.target.clone()).blueprint(context, nodes)
+	}
+}
+
+impl<State: ValidState, T: Tween, E: Element<State>, F: Fn(T) -> E + Send + Sync + 'static>
+	Element<State> for Animated<State, T, E, F>
+{
+}
+
+#[tokio::test]
+async fn asteroids_animated_element() {
+	use crate::{Projector, Reify, elements::Spatial};
+
+	struct TestState {
+		x: f32,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Animated::transform(
+				Spatial::default().pos([self.x, 0.0, 0.0]),
+				Duration::from_secs(1),
+				Easing::Linear,
+			)
+		}
+	}
+	fn x(projector: &Projector<TestState>) -> f32 {
+		let spatial = projector.scene().unwrap().find("Spatial").next().unwrap();
+		spatial.transform.unwrap().translation.unwrap().x
+	}
+
+	let context = Context::headless().await.unwrap();
+	let info = FrameInfo {
+		delta: 0.25,
+		elapsed: 0.0,
+	};
+	let mut state = TestState { x: 0.0 };
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	assert_eq!(x(&projector), 0.0);
+
+	state.x = 1.0;
+	projector.update(&context, &mut state);
+	assert_eq!(x(&projector), 0.0);
+	for _ in 0..2 {
+		projector.frame(&context, &info, &mut state);
+		assert!(projector.update_requested());
+		projector.update(&context, &mut state);
+	}
+	assert_eq!(x(&projector), 0.5);
+
+	for _ in 0..2 {
+		projector.frame(&context, &info, &mut state);
+		projector.update(&context, &mut state);
+	}
+	assert_eq!(x(&projector), 1.0);
+	projector.frame(&context, &info, &mut state);
+	assert!(!projector.update_requested());
+}
+
+#[tokio::test]
+async fn asteroids_animated_text_color() {
+	use crate::{Projector, Reify, elements::Text};
+
+	struct TestState {
+		color: Color,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Animated::new(
+				self.color,
+				Duration::from_secs(1),
+				Easing::Linear,
+				|color| Text::new("hi").color(color).build(),
+			)
+		}
+	}
+	fn shown(projector: &Projector<TestState>) -> String {
+		let scene = projector.scene().unwrap();
+		assert_eq!(scene.find("Text").count(), 1);
+		scene.find("Text").next().unwrap().debug.clone()
+	}
+
+	let context = Context::headless().await.unwrap();
+	let info = FrameInfo {
+		delta: 0.5,
+		elapsed: 0.0,
+	};
+	let white = rgba_linear!(1.0, 1.0, 1.0, 1.0);
+	let green = rgba_linear!(0.0, 1.0, 0.0, 1.0);
+	let mut state = TestState { color: white };
+	let mut projector = Projector::headless(&state, &context, "/".into());
+
+	state.color = green;
+	projector.update(&context, &mut state);
+	assert_eq!(
+		shown(&projector),
+		format!("{:?}", Text::new("hi").color(white))
+	);
+	projector.frame(&context, &info, &mut state);
+	projector.update(&context, &mut state);
+	let halfway = rgba_linear!(0.5, 1.0, 0.5, 1.0);
+	assert_eq!(
+		shown(&projector),
+		format!("{:?}", Text::new("hi").color(halfway))
+	);
+
+	projector.frame(&context, &info, &mut state);
+	projector.update(&context, &mut state);
+	assert_eq!(
+		shown(&projector),
+		format!("{:?}", Text::new("hi").color(green))
+	);
+}
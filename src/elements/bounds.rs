@@ -68,7 +68,13 @@ impl<State: ValidState> CustomElement<State> for Bounds<State> {
 		})
 	}
 
-	fn diff(&self, old_self: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		self.apply_transform(old_self, &inner.spatial);
 	}
 
@@ -1,5 +1,5 @@
 use crate::{
-	CreateInnerInfo, ValidState,
+	CreateInnerInfo, Update, ValidState,
 	context::Context,
 	custom::{CustomElement, FnWrapper, Transformable},
 };
@@ -8,7 +8,7 @@ use derive_where::derive_where;
 use mint::Vector2;
 use stardust_xr_fusion::{
 	node::NodeError,
-	spatial::{SpatialRef, Transform},
+	spatial::{BoundingBox, SpatialRef, Transform},
 };
 use stardust_xr_molecules::{DebugSettings, UIElement, VisualDebug, button::ButtonVisualSettings};
 
@@ -43,6 +43,15 @@ impl<State: ValidState> Button<State> {
 		}
 	}
 }
+impl<State: Update> Button<State>
+where
+	State::Message: Clone,
+{
+	/// Dispatch `message` when pressed instead of calling a closure.
+	pub fn emit(message: State::Message) -> Button<State> {
+		Button::new(move |state: &mut State| state.dispatch(message.clone()))
+	}
+}
 impl<State: ValidState> CustomElement<State> for Button<State> {
 	type Inner = stardust_xr_molecules::button::Button;
 	type Resource = ();
@@ -70,7 +79,13 @@ impl<State: ValidState> CustomElement<State> for Button<State> {
 		Ok(button)
 	}
 
-	fn diff(&self, old: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		self.apply_transform(old, inner.touch_plane().root());
 		// if self.size != old.size {
 		//     inner.touch_plane().set_size(self.size);
@@ -93,6 +108,12 @@ impl<State: ValidState> CustomElement<State> for Button<State> {
 	fn spatial_aspect<'a>(&self, inner: &Self::Inner) -> SpatialRef {
 		inner.touch_plane().root().clone().as_spatial_ref()
 	}
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		Some(BoundingBox {
+			center: [0.0; 3].into(),
+			size: [self.size.x, self.size.y, 0.0].into(),
+		})
+	}
 }
 impl<State: ValidState> Transformable for Button<State> {
 	fn transform(&self) -> &Transform {
@@ -133,3 +154,33 @@ async fn asteroids_button_element() {
 
 	client::run::<TestState>(&[]).await
 }
+
+#[tokio::test]
+async fn asteroids_button_emit() {
+	#[derive(Debug, Clone, PartialEq)]
+	enum Message {
+		Increment,
+	}
+	#[derive(Default)]
+	struct Counter {
+		count: u32,
+		messages: Vec<Message>,
+	}
+	impl Update for Counter {
+		type Message = Message;
+
+		fn update(&mut self, message: Message) {
+			match &message {
+				Message::Increment => self.count += 1,
+			}
+			self.messages.push(message);
+		}
+	}
+
+	let button = Button::<Counter>::emit(Message::Increment);
+	let mut state = Counter::default();
+	(button.on_press.0)(&mut state);
+	(button.on_press.0)(&mut state);
+	assert_eq!(state.count, 2);
+	assert_eq!(state.messages, [Message::Increment, Message::Increment]);
+}
@@ -1,5 +1,5 @@
 use crate::{
-	Context, CreateInnerInfo, ValidState,
+	Context, CreateInnerInfo, Update, ValidState,
 	custom::{CustomElement, FnWrapper, Transformable},
 };
 use derive_setters::Setters;
@@ -64,6 +64,17 @@ impl<State: ValidState> Dial<State> {
 		}
 	}
 }
+impl<State: Update> Dial<State> {
+	/// Dispatch the message `on_change` makes from the new value instead of calling a closure.
+	pub fn emit(
+		current_value: f32,
+		on_change: impl Fn(f32) -> State::Message + Send + Sync + 'static,
+	) -> Dial<State> {
+		Dial::create(current_value, move |state: &mut State, value| {
+			state.dispatch(on_change(value))
+		})
+	}
+}
 
 impl<State: ValidState> CustomElement<State> for Dial<State> {
 	// You'll need to create this type in stardust_xr_molecules
@@ -86,7 +97,13 @@ impl<State: ValidState> CustomElement<State> for Dial<State> {
 		)
 	}
 
-	fn diff(&self, old: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		self.apply_transform(old, &inner.root);
 		if self.radius != old.radius || self.thickness != old.thickness {
 			let _ = inner.field.set_shape(Shape::Cylinder(CylinderShape {
@@ -0,0 +1,419 @@
+use crate::{
+	Context, Element, ValidState,
+	blueprint::BlueprintNode,
+	custom::FnWrapper,
+	element::{ElementDiffer, ElementError, ParentSpace, gen_inner_key},
+	inner::ElementInnerMap,
+	resource::ResourceRegistry,
+};
+use stardust_xr_fusion::{root::FrameInfo, spatial::BoundingBox};
+use std::{
+	hash::{DefaultHasher, Hash, Hasher},
+	path::Path,
+	sync::OnceLock,
+};
+
+#[derive(Default)]
+struct ErrorBoundaryInner {
+	failed: bool,
+	/// Errors caught since the last frame, handed to `on_error` on the next one
+	pending: Vec<ElementError>,
+}
+
+/// Catches elements in `content` that fail to create and shows `fallback` in their place.
+///
+/// Once content fails it stays replaced by the fallback until `reset_key` changes,
+/// so you can e.g. retry loading a model after fixing its path.
+pub struct ErrorBoundary<State: ValidState, C: Element<State>, F: Element<State>> {
+	content: C,
+	fallback: F,
+	on_error: FnWrapper<dyn Fn(&mut State, &[ElementError]) + Send + Sync>,
+	reset_key: u64,
+	inner_key: OnceLock<u64>,
+}
+impl<State: ValidState, C: Element<State>, F: Element<State>> ErrorBoundary<State, C, F> {
+	pub fn new(content: C, fallback: F) -> Self {
+		ErrorBoundary {
+			content,
+			fallback,
+			on_error: FnWrapper(Box::new(|_, _| {})),
+			reset_key: 0,
+			inner_key: OnceLock::new(),
+		}
+	}
+	/// Called on the next frame with every error caught since the last one.
+	pub fn on_error(
+		mut self,
+		on_error: impl Fn(&mut State, &[ElementError]) + Send + Sync + 'static,
+	) -> Self {
+		self.on_error = FnWrapper(Box::new(on_error));
+		self
+	}
+	/// When this changes, a failed boundary tries to create its content again.
+	pub fn reset_key(mut self, reset_key: impl Hash) -> Self {
+		let mut hasher = DefaultHasher::new();
+		reset_key.hash(&mut hasher);
+		self.reset_key = hasher.finish();
+		self
+	}
+
+	fn content_key(inner_key: u64) -> u64 {
+		gen_inner_key::<C>(inner_key, 0)
+	}
+	fn fallback_key(inner_key: u64) -> u64 {
+		gen_inner_key::<F>(inner_key, 1)
+	}
+
+	/// Create the content, swapping it for the fallback if anything inside failed
+	fn create_content(
+		&self,
+		inner_key: u64,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let error_start = inner_map.error_count();
+		self.content.create_inner_recursive(
+			Self::content_key(inner_key),
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+		let errors = inner_map.take_errors_since(error_start);
+		if !errors.is_empty() {
+			self.content.destroy_inner_recursive(inner_map);
+			self.fail(
+				errors,
+				inner_key,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			);
+		}
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	fn fail(
+		&self,
+		errors: Vec<ElementError>,
+		inner_key: u64,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		if let Some(inner) = inner_map.get_raw_mut::<ErrorBoundaryInner>(inner_key) {
+			inner.failed = true;
+			inner.pending.extend(errors);
+		}
+		self.fallback.create_inner_recursive(
+			Self::fallback_key(inner_key),
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+	}
+
+	fn failed(inner_key: u64, inner_map: &ElementInnerMap) -> bool {
+		inner_map
+			.get_raw::<ErrorBoundaryInner>(inner_key)
+			.is_some_and(|inner| inner.failed)
+	}
+}
+
+impl<State: ValidState, C: Element<State>, F: Element<State>> ElementDiffer<State>
+	for ErrorBoundary<State, C, F>
+{
+	fn create_inner_recursive(
+		&self,
+		inner_key: u64,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let _ = self.inner_key.set(inner_key);
+		inner_map.insert_raw(inner_key, ErrorBoundaryInner::default());
+		self.create_content(
+			inner_key,
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+	}
+
+	fn frame_recursive(
+		&self,
+		context: &Context,
+		info: &FrameInfo,
+		state: &mut State,
+		inner_map: &mut ElementInnerMap,
+	) {
+		let Some(&inner_key) = self.inner_key.get() else {
+			return;
+		};
+		let Some(inner) = inner_map.get_raw_mut::<ErrorBoundaryInner>(inner_key) else {
+			return;
+		};
+		let failed = inner.failed;
+		let pending = std::mem::take(&mut inner.pending);
+		if !pending.is_empty() {
+			(self.on_error.0)(state, &pending);
+		}
+
+		if failed {
+			self.fallback
+				.frame_recursive(context, info, state, inner_map);
+		} else {
+			self.content
+				.frame_recursive(context, info, state, inner_map);
+		}
+	}
+
+	fn diff_same_type(
+		&self,
+		inner_key: u64,
+		old: &Self,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let _ = self.inner_key.set(inner_key);
+
+		if !Self::failed(inner_key, inner_map) {
+			let error_start = inner_map.error_count();
+			self.content.diff_same_type(
+				Self::content_key(inner_key),
+				&old.content,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			);
+			let errors = inner_map.take_errors_since(error_start);
+			if !errors.is_empty() {
+				self.content.destroy_inner_recursive(inner_map);
+				self.fail(
+					errors,
+					inner_key,
+					context,
+					parent_space,
+					element_path,
+					inner_map,
+					resources,
+				);
+			}
+		} else if self.reset_key != old.reset_key {
+			old.fallback.destroy_inner_recursive(inner_map);
+			if let Some(inner) = inner_map.get_raw_mut::<ErrorBoundaryInner>(inner_key) {
+				inner.failed = false;
+			}
+			self.create_content(
+				inner_key,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			);
+		} else {
+			self.fallback.diff_same_type(
+				Self::fallback_key(inner_key),
+				&old.fallback,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			);
+		}
+	}
+
+	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
+		let Some(&inner_key) = self.inner_key.get() else {
+			return;
+		};
+		if Self::failed(inner_key, inner_map) {
+			self.fallback.destroy_inner_recursive(inner_map);
+		} else {
+			self.content.destroy_inner_recursive(inner_map);
+		}
+		inner_map.remove(inner_key);
+	}
+
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		self.content.bounds_hint()
+	}
+
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		// only the content, the fallback is never part of a healthy tree
+		self.content.blueprint(context, nodes)
+	}
+}
+
+impl<State: ValidState, C: Element<State>, F: Element<State>> Element<State>
+	for ErrorBoundary<State, C, F>
+{
+}
+
+#[tokio::test]
+#[ignore = "interactive demo, needs a running Stardust server"]
+async fn asteroids_error_boundary_element() {
+	use crate::{
+		CreateInnerInfo,
+		client::{self, ClientState},
+		custom::{CustomElement, LocalTransform},
+		elements::Text,
+	};
+	use serde::{Deserialize, Serialize};
+	use stardust_xr_fusion::spatial::SpatialRef;
+
+	#[derive(Debug)]
+	struct Broken;
+	impl<State: ValidState> CustomElement<State> for Broken {
+		type Inner = stardust_xr_fusion::spatial::Spatial;
+		type Resource = ();
+		type Error = &'static str;
+
+		fn create_inner(
+			&self,
+			_context: &Context,
+			_info: CreateInnerInfo,
+			_resource: &mut Self::Resource,
+		) -> Result<Self::Inner, Self::Error> {
+			Err("broken on purpose")
+		}
+		fn diff(
+			&self,
+			_context: &Context,
+			_old_self: &Self,
+			_inner: &mut Self::Inner,
+			_resource: &mut (),
+		) {
+		}
+		fn spatial_aspect(&self, inner: &Self::Inner) -> SpatialRef {
+			inner.clone().as_spatial_ref()
+		}
+	}
+
+	impl LocalTransform for Broken {}
+
+	#[derive(Default, Serialize, Deserialize)]
+	struct TestState {
+		errors: Vec<String>,
+	}
+	impl crate::util::Migrate for TestState {
+		type Old = Self;
+	}
+	impl ClientState for TestState {
+		const APP_ID: &'static str = "org.asteroids.error_boundary";
+	}
+	impl crate::Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			ErrorBoundary::new(
+				Broken.build().child(Text::new("never shown").build()),
+				Text::new("fallback").build(),
+			)
+			.on_error(|state: &mut Self, errors| {
+				state.errors.extend(errors.iter().map(ToString::to_string));
+			})
+		}
+	}
+
+	client::run::<TestState>(&[]).await
+}
+
+#[tokio::test]
+async fn asteroids_error_boundary_fallback() {
+	use crate::{CustomElement, Projector, Reify, elements::Text};
+
+	/// Reports a failed create like a `CustomElement` does when the server refuses it
+	struct Failing;
+	impl<State: ValidState> ElementDiffer<State> for Failing {
+		fn create_inner_recursive(
+			&self,
+			_inner_key: u64,
+			_context: &Context,
+			_parent_space: &ParentSpace,
+			element_path: &Path,
+			inner_map: &mut ElementInnerMap,
+			_resources: &mut ResourceRegistry,
+		) {
+			inner_map.push_error(ElementError {
+				element_type: "Failing",
+				element_path: element_path.to_path_buf(),
+				message: "broken on purpose".to_string(),
+			});
+		}
+		fn frame_recursive(
+			&self,
+			_context: &Context,
+			_info: &FrameInfo,
+			_state: &mut State,
+			_inner_map: &mut ElementInnerMap,
+		) {
+		}
+		fn diff_same_type(
+			&self,
+			_inner_key: u64,
+			_old: &Self,
+			_context: &Context,
+			_parent_space: &ParentSpace,
+			_element_path: &Path,
+			_inner_map: &mut ElementInnerMap,
+			_resources: &mut ResourceRegistry,
+		) {
+		}
+		fn destroy_inner_recursive(&self, _inner_map: &mut ElementInnerMap) {}
+		fn blueprint(&self, _context: &Context, _nodes: &mut Vec<BlueprintNode>) {}
+	}
+	impl<State: ValidState> Element<State> for Failing {}
+
+	#[derive(Default)]
+	struct TestState {
+		errors: Vec<String>,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			ErrorBoundary::new(Failing, Text::new("fallback").build()).on_error(
+				|state: &mut Self, errors| {
+					state
+						.errors
+						.extend(errors.iter().map(|error| error.message.clone()))
+				},
+			)
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let info = FrameInfo {
+		delta: 0.0,
+		elapsed: 0.0,
+	};
+	let mut state = TestState::default();
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	let fallback = projector.scene().unwrap().find("Text").next().unwrap();
+	assert!(fallback.debug.contains("fallback"));
+
+	// errors reach `on_error` on the next frame, only once
+	projector.frame(&context, &info, &mut state);
+	projector.update(&context, &mut state);
+	projector.frame(&context, &info, &mut state);
+	assert_eq!(state.errors, ["broken on purpose"]);
+	assert_eq!(projector.scene().unwrap().find("Text").count(), 1);
+}
@@ -213,7 +213,13 @@ impl<State: ValidState> CustomElement<State> for FieldViz {
 		})
 	}
 
-	fn diff(&self, old: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		if self.shape != old.shape {
 			let _ = inner.field.set_shape(self.shape.clone());
 
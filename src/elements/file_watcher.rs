@@ -1,13 +1,13 @@
 use crate::{
 	Context, CreateInnerInfo, ValidState,
-	custom::{CustomElement, FnWrapper},
+	custom::{CustomElement, FnWrapper, LocalTransform},
 };
 use derive_where::derive_where;
 use futures_util::StreamExt;
 use inotify::{EventMask, Inotify, WatchMask};
 use stardust_xr_fusion::spatial::SpatialRef;
 use std::{
-	path::PathBuf,
+	path::{Path, PathBuf},
 	sync::{
 		Arc,
 		atomic::{AtomicBool, Ordering},
@@ -15,17 +15,88 @@ use std::{
 };
 use tokio::task::AbortHandle;
 
-pub struct FileWatcherInner {
-	spatial: SpatialRef,
+/// Watches a file for modifications in the background until dropped.
+pub(crate) struct FileWatch {
 	watch_loop: AbortHandle,
 	modified: Arc<AtomicBool>,
 }
-impl Drop for FileWatcherInner {
+impl FileWatch {
+	/// Watch the file itself, it has to exist already.
+	pub(crate) fn new(file_path: PathBuf) -> Self {
+		Self::spawn(|modified| Self::modify_loop(file_path, modified))
+	}
+	/// Watch the file's directory for it being written, replaced (like most editors save)
+	/// or created after the watch started. The directory has to exist already.
+	pub(crate) fn directory_entry(file_path: PathBuf) -> Self {
+		Self::spawn(|modified| Self::directory_loop(file_path, modified))
+	}
+	/// If the file was modified since the last call
+	pub(crate) fn take_modified(&self) -> bool {
+		self.modified.swap(false, Ordering::Relaxed)
+	}
+
+	fn spawn<F: Future<Output = std::io::Result<()>> + Send + 'static>(
+		watch: impl FnOnce(Arc<AtomicBool>) -> F,
+	) -> Self {
+		let modified = Arc::new(AtomicBool::new(false));
+		let watch_loop = tokio::spawn(watch(modified.clone())).abort_handle();
+		FileWatch {
+			watch_loop,
+			modified,
+		}
+	}
+	async fn modify_loop(file_path: PathBuf, modified: Arc<AtomicBool>) -> std::io::Result<()> {
+		let inotify = Inotify::init()?;
+		let _watcher = inotify.watches().add(file_path, WatchMask::MODIFY)?;
+		let mut event_stream = inotify.into_event_stream([0; 1024])?;
+
+		while let Some(Ok(event)) = event_stream.next().await {
+			if event.mask.contains(EventMask::MODIFY) {
+				modified.store(true, Ordering::Relaxed);
+			}
+		}
+
+		Ok(())
+	}
+	async fn directory_loop(file_path: PathBuf, modified: Arc<AtomicBool>) -> std::io::Result<()> {
+		let Some(file_name) = file_path.file_name() else {
+			return Err(std::io::ErrorKind::InvalidInput.into());
+		};
+		let dir = match file_path.parent() {
+			Some(dir) if !dir.as_os_str().is_empty() => dir,
+			_ => Path::new("."),
+		};
+		let inotify = Inotify::init()?;
+		let _watcher = inotify.watches().add(
+			dir,
+			WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::CREATE,
+		)?;
+		let mut event_stream = inotify.into_event_stream([0; 1024])?;
+
+		while let Some(Ok(event)) = event_stream.next().await {
+			if event.name.as_deref() == Some(file_name)
+				&& event
+					.mask
+					.intersects(EventMask::CLOSE_WRITE | EventMask::MOVED_TO | EventMask::CREATE)
+			{
+				modified.store(true, Ordering::Relaxed);
+			}
+		}
+
+		Ok(())
+	}
+}
+impl Drop for FileWatch {
 	fn drop(&mut self) {
 		self.watch_loop.abort();
 	}
 }
 
+pub struct FileWatcherInner {
+	spatial: SpatialRef,
+	watch: FileWatch,
+}
+
 #[derive_where(Debug)]
 pub struct FileWatcher<State: ValidState> {
 	file_path: PathBuf,
@@ -41,20 +112,6 @@ impl<State: ValidState> FileWatcher<State> {
 			on_change: FnWrapper(Box::new(on_change)),
 		}
 	}
-
-	async fn watch_loop(file_path: PathBuf, modified: Arc<AtomicBool>) -> std::io::Result<()> {
-		let inotify = Inotify::init()?;
-		let _watcher = inotify.watches().add(file_path, WatchMask::MODIFY)?;
-		let mut event_stream = inotify.into_event_stream([0; 1024])?;
-
-		while let Some(Ok(event)) = event_stream.next().await {
-			if event.mask.contains(EventMask::MODIFY) {
-				modified.store(true, Ordering::Relaxed);
-			}
-		}
-
-		Ok(())
-	}
 }
 // TODO: make one watch_loop as a resource to only have one Inotify instance
 impl<State: ValidState> CustomElement<State> for FileWatcher<State> {
@@ -68,26 +125,21 @@ impl<State: ValidState> CustomElement<State> for FileWatcher<State> {
 		info: CreateInnerInfo,
 		_resource: &mut Self::Resource,
 	) -> Result<Self::Inner, Self::Error> {
-		let modified = Arc::new(AtomicBool::new(false));
-		let watch_loop =
-			tokio::spawn(Self::watch_loop(self.file_path.clone(), modified.clone())).abort_handle();
-
 		Ok(FileWatcherInner {
 			spatial: info.parent_space.clone(),
-			watch_loop,
-			modified,
+			watch: FileWatch::new(self.file_path.clone()),
 		})
 	}
 
-	fn diff(&self, old_self: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		if old_self.file_path != self.file_path {
-			inner.watch_loop.abort();
-			inner.modified.store(false, Ordering::Relaxed);
-			inner.watch_loop = tokio::spawn(Self::watch_loop(
-				self.file_path.clone(),
-				inner.modified.clone(),
-			))
-			.abort_handle();
+			inner.watch = FileWatch::new(self.file_path.clone());
 		}
 	}
 
@@ -98,8 +150,7 @@ impl<State: ValidState> CustomElement<State> for FileWatcher<State> {
 		state: &mut State,
 		inner: &mut Self::Inner,
 	) {
-		if inner.modified.load(Ordering::Relaxed) {
-			inner.modified.store(false, Ordering::Relaxed);
+		if inner.watch.take_modified() {
 			(self.on_change.0)(state);
 		}
 	}
@@ -108,3 +159,46 @@ impl<State: ValidState> CustomElement<State> for FileWatcher<State> {
 		inner.spatial.clone()
 	}
 }
+
+impl<State: ValidState> LocalTransform for FileWatcher<State> {}
+
+#[tokio::test]
+async fn asteroids_file_watch() {
+	use std::time::Duration;
+
+	let dir = std::env::temp_dir().join(format!("asteroids_file_watch_{}", std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	let path = dir.join("state.ron");
+	let watch = FileWatch::directory_entry(path.clone());
+
+	// the file doesn't exist yet, then gets written elsewhere and moved over like an editor would
+	let modified = tokio::time::timeout(Duration::from_secs(5), async {
+		loop {
+			std::fs::write(dir.join("state.ron.tmp"), "()").unwrap();
+			std::fs::rename(dir.join("state.ron.tmp"), &path).unwrap();
+			tokio::time::sleep(Duration::from_millis(50)).await;
+			if watch.take_modified() {
+				break;
+			}
+		}
+	})
+	.await;
+	assert!(modified.is_ok());
+
+	// other files in the same directory don't count
+	tokio::time::sleep(Duration::from_millis(50)).await;
+	watch.take_modified();
+	std::fs::write(dir.join("other.ron"), "()").unwrap();
+	tokio::time::sleep(Duration::from_millis(50)).await;
+	assert!(!watch.take_modified());
+
+	std::fs::write(&path, "(a: 1)").unwrap();
+	let modified = tokio::time::timeout(Duration::from_secs(5), async {
+		while !watch.take_modified() {
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+	})
+	.await;
+	assert!(modified.is_ok());
+	let _ = std::fs::remove_dir_all(&dir);
+}
@@ -2,7 +2,7 @@ use std::path::{Path, PathBuf};
 
 use crate::{
 	Context, CreateInnerInfo, ValidState,
-	custom::{CustomElement, FnWrapper, derive_setters::Setters},
+	custom::{CustomElement, FnWrapper, LocalTransform, derive_setters::Setters},
 };
 use derive_where::derive_where;
 use glam::Vec3;
@@ -74,7 +74,13 @@ impl<State: ValidState> CustomElement<State> for GrabRing<State> {
 		)
 	}
 
-	fn diff(&self, old_self: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		if self.radius != old_self.radius || self.thickness != old_self.thickness {
 			inner.resize(self.radius, self.thickness);
 		}
@@ -98,6 +104,8 @@ impl<State: ValidState> CustomElement<State> for GrabRing<State> {
 	}
 }
 
+impl<State: ValidState> LocalTransform for GrabRing<State> {}
+
 pub struct GrabRingInner {
 	connection: Connection,
 	path: PathBuf,
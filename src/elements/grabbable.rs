@@ -1,5 +1,5 @@
-use crate::ValidState;
-use crate::custom::{CustomElement, FnWrapper};
+use crate::custom::{CustomElement, FnWrapper, LocalTransform};
+use crate::{Update, ValidState};
 use derive_setters::Setters;
 use mint::{Quaternion, Vector3};
 use stardust_xr_fusion::{
@@ -80,6 +80,31 @@ impl<State: ValidState> Grabbable<State> {
 		self
 	}
 }
+impl<State: Update> Grabbable<State> {
+	/// Dispatch the message `on_change` makes from the new pose instead of calling a closure.
+	pub fn emit(
+		field_shape: Shape,
+		pos: impl Into<Vector3<f32>>,
+		rot: impl Into<Quaternion<f32>>,
+		on_change: impl Fn(Vector3<f32>, Quaternion<f32>) -> State::Message + Send + Sync + 'static,
+	) -> Self {
+		Self::new(field_shape, pos, rot, move |state: &mut State, pos, rot| {
+			state.dispatch(on_change(pos, rot))
+		})
+	}
+	pub fn emit_grab_start(self, message: State::Message) -> Self
+	where
+		State::Message: Clone,
+	{
+		self.grab_start(move |state: &mut State| state.dispatch(message.clone()))
+	}
+	pub fn emit_grab_stop(self, message: State::Message) -> Self
+	where
+		State::Message: Clone,
+	{
+		self.grab_stop(move |state: &mut State| state.dispatch(message.clone()))
+	}
+}
 impl<State: ValidState> CustomElement<State> for Grabbable<State> {
 	type Inner = stardust_xr_molecules::Grabbable;
 	type Resource = ();
@@ -115,7 +140,13 @@ impl<State: ValidState> CustomElement<State> for Grabbable<State> {
 		Ok(grabbable)
 	}
 
-	fn diff(&self, old_self: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &crate::Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		if self.field_shape != old_self.field_shape {
 			let _ = inner.field().set_shape(self.field_shape.clone());
 		}
@@ -153,6 +184,8 @@ impl<State: ValidState> CustomElement<State> for Grabbable<State> {
 	}
 }
 
+impl<State: ValidState> LocalTransform for Grabbable<State> {}
+
 #[tokio::test]
 async fn asteroids_grabbable_element() {
 	use crate::{
@@ -2,7 +2,7 @@ use std::f32::consts::FRAC_PI_2;
 
 use crate::{
 	Context, CreateInnerInfo, ValidState,
-	custom::{CustomElement, FnWrapper, derive_setters::Setters},
+	custom::{CustomElement, FnWrapper, LocalTransform, derive_setters::Setters},
 };
 use derive_where::derive_where;
 use glam::{Mat4, Vec3, vec3};
@@ -60,7 +60,13 @@ impl<State: ValidState> CustomElement<State> for Handle<State> {
 		HandleInner::new(info.parent_space, self.pos)
 	}
 
-	fn diff(&self, old_self: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		if self.pos != old_self.pos {
 			// Update the position of the handle
 			let _ = inner
@@ -86,6 +92,8 @@ impl<State: ValidState> CustomElement<State> for Handle<State> {
 	}
 }
 
+impl<State: ValidState> LocalTransform for Handle<State> {}
+
 pub struct HandleInner {
 	_field: Field,
 	input: InputQueue,
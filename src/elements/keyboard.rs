@@ -80,7 +80,13 @@ impl<State: ValidState> CustomElement<State> for KeyboardHandler<State> {
 		})
 	}
 
-	fn diff(&self, old: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		self.apply_transform(old, &inner.field);
 
 		if self.field_shape != old.field_shape {
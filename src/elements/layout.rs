@@ -0,0 +1,443 @@
+use crate::{
+	Context, Element, ValidState,
+	blueprint::BlueprintNode,
+	custom::{CustomElement, Transformable},
+	element::{ElementDiffer, ElementWrapper, KeyedChildren, ParentSpace, gen_inner_key},
+	elements::{AnimatedInner, Easing, Spatial},
+	inner::ElementInnerMap,
+	resource::ResourceRegistry,
+};
+use derive_setters::Setters;
+use glam::{Quat, Vec3};
+use mint::Vector2;
+use stardust_xr_fusion::{
+	root::FrameInfo,
+	spatial::{BoundingBox, Transform},
+};
+use std::{
+	f32::consts::{FRAC_PI_2, TAU},
+	hash::Hash,
+	path::Path,
+	sync::{Mutex, OnceLock},
+	time::Duration,
+};
+
+/// Places children by their index, wrapping each one in a spatial at its slot.
+///
+/// When the children change only the slots that moved get new transforms,
+/// and with [`Layout::keyed_children`] a child that changes index keeps its inner
+/// and eases to its new slot over `duration`.
+pub trait Layout: Transformable + Sized {
+	/// Transform of the slot at `index` out of `count` children
+	fn slot(&self, index: usize, count: usize) -> Transform;
+	/// How long children take to move to a new slot, zero to jump straight there
+	fn animation(&self) -> (Duration, Easing);
+
+	fn children<State: ValidState, E: Element<State>>(
+		self,
+		children: impl IntoIterator<Item = E>,
+	) -> ElementWrapper<State, Spatial, ((), Vec<Slot<State, E>>)> {
+		let children = children.into_iter().collect::<Vec<_>>();
+		let count = children.len();
+		let (duration, easing) = self.animation();
+		let slots = children
+			.into_iter()
+			.enumerate()
+			.map(|(index, child)| Slot::new(self.slot(index, count), duration, easing, child))
+			.collect();
+		Spatial(*self.transform()).build().child(slots)
+	}
+	/// Children identified by a key, so moving one to another index animates it instead of recreating it
+	fn keyed_children<
+		State: ValidState,
+		K: Hash + Eq + Clone + Send + Sync + 'static,
+		E: Element<State>,
+	>(
+		self,
+		children: impl IntoIterator<Item = (K, E)>,
+	) -> ElementWrapper<State, Spatial, ((), KeyedChildren<K, Slot<State, E>>)> {
+		let children = children.into_iter().collect::<Vec<_>>();
+		let count = children.len();
+		let (duration, easing) = self.animation();
+		Spatial(*self.transform())
+			.build()
+			.keyed_children(
+				children
+					.into_iter()
+					.enumerate()
+					.map(|(index, (key, child))| {
+						(
+							key,
+							Slot::new(self.slot(index, count), duration, easing, child),
+						)
+					}),
+			)
+	}
+}
+
+/// Rows and columns on a plane, centered on the origin and filled left to right, top to bottom.
+#[derive(Debug, Clone, Setters)]
+#[setters(into)]
+pub struct Grid {
+	transform: Transform,
+	columns: usize,
+	/// Distance between the centers of neighboring cells
+	spacing: Vector2<f32>,
+	duration: Duration,
+	easing: Easing,
+}
+impl Grid {
+	pub fn new(columns: usize, spacing: impl Into<Vector2<f32>>) -> Self {
+		Grid {
+			transform: Transform::none(),
+			columns: columns.max(1),
+			spacing: spacing.into(),
+			duration: Duration::ZERO,
+			easing: Easing::default(),
+		}
+	}
+}
+impl Layout for Grid {
+	fn slot(&self, index: usize, count: usize) -> Transform {
+		let columns = self.columns.max(1);
+		let (column, row) = (index % columns, index / columns);
+		let used_columns = count.min(columns);
+		let rows = count.div_ceil(columns);
+		Transform::from_translation([
+			(column as f32 - (used_columns as f32 - 1.0) / 2.0) * self.spacing.x,
+			((rows as f32 - 1.0) / 2.0 - row as f32) * self.spacing.y,
+			0.0,
+		])
+	}
+	fn animation(&self) -> (Duration, Easing) {
+		(self.duration, self.easing)
+	}
+}
+impl Transformable for Grid {
+	fn transform(&self) -> &Transform {
+		&self.transform
+	}
+	fn transform_mut(&mut self) -> &mut Transform {
+		&mut self.transform
+	}
+}
+
+/// A grid wrapped around the inside of a cylinder with the origin on its axis,
+/// every cell turned to face the axis. Handy for launchers around the user.
+#[derive(Debug, Clone, Setters)]
+#[setters(into)]
+pub struct CylinderGrid {
+	transform: Transform,
+	radius: f32,
+	columns: usize,
+	/// Angle in radians between neighboring columns
+	column_angle: f32,
+	/// Distance between the centers of neighboring rows
+	row_height: f32,
+	duration: Duration,
+	easing: Easing,
+}
+impl CylinderGrid {
+	pub fn new(radius: f32, columns: usize, column_angle: f32, row_height: f32) -> Self {
+		CylinderGrid {
+			transform: Transform::none(),
+			radius,
+			columns: columns.max(1),
+			column_angle,
+			row_height,
+			duration: Duration::ZERO,
+			easing: Easing::default(),
+		}
+	}
+}
+impl Layout for CylinderGrid {
+	fn slot(&self, index: usize, count: usize) -> Transform {
+		let columns = self.columns.max(1);
+		let (column, row) = (index % columns, index / columns);
+		let used_columns = count.min(columns);
+		let rows = count.div_ceil(columns);
+		// the middle column sits straight ahead along -Z
+		let angle = (column as f32 - (used_columns as f32 - 1.0) / 2.0) * -self.column_angle;
+		let y = ((rows as f32 - 1.0) / 2.0 - row as f32) * self.row_height;
+		Transform::from_translation_rotation(
+			[-angle.sin() * self.radius, y, -angle.cos() * self.radius],
+			Quat::from_rotation_y(angle),
+		)
+	}
+	fn animation(&self) -> (Duration, Easing) {
+		(self.duration, self.easing)
+	}
+}
+impl Transformable for CylinderGrid {
+	fn transform(&self) -> &Transform {
+		&self.transform
+	}
+	fn transform_mut(&mut self) -> &mut Transform {
+		&mut self.transform
+	}
+}
+
+/// Children spread clockwise along a circle or arc on the XY plane, like a radial menu.
+#[derive(Debug, Clone, Setters)]
+#[setters(into)]
+pub struct Radial {
+	transform: Transform,
+	radius: f32,
+	/// Angle in radians of the first child, counterclockwise from +X
+	start_angle: f32,
+	/// Angle in radians the children are spread over, a full circle by default
+	sweep: f32,
+	duration: Duration,
+	easing: Easing,
+}
+impl Radial {
+	pub fn new(radius: f32) -> Self {
+		Radial {
+			transform: Transform::none(),
+			radius,
+			start_angle: FRAC_PI_2,
+			sweep: TAU,
+			duration: Duration::ZERO,
+			easing: Easing::default(),
+		}
+	}
+}
+impl Layout for Radial {
+	fn slot(&self, index: usize, count: usize) -> Transform {
+		// a full circle would put the last child on top of the first
+		let step = if self.sweep.abs() >= TAU {
+			self.sweep / count.max(1) as f32
+		} else {
+			self.sweep / count.saturating_sub(1).max(1) as f32
+		};
+		let angle = self.start_angle - step * index as f32;
+		Transform::from_translation([angle.cos() * self.radius, angle.sin() * self.radius, 0.0])
+	}
+	fn animation(&self) -> (Duration, Easing) {
+		(self.duration, self.easing)
+	}
+}
+impl Transformable for Radial {
+	fn transform(&self) -> &Transform {
+		&self.transform
+	}
+	fn transform_mut(&mut self) -> &mut Transform {
+		&mut self.transform
+	}
+}
+
+/// A child of a [`Layout`], placed at its slot and easing there when the slot moves.
+pub struct Slot<State: ValidState, E: Element<State>> {
+	target: Transform,
+	duration: Duration,
+	easing: Easing,
+	/// Moved into `spatial` once we know where the slot currently is
+	child: Mutex<Option<E>>,
+	spatial: OnceLock<ElementWrapper<State, Spatial, ((), E)>>,
+	inner_key: OnceLock<u64>,
+}
+impl<State: ValidState, E: Element<State>> Slot<State, E> {
+	fn new(target: Transform, duration: Duration, easing: Easing, child: E) -> Self {
+		Slot {
+			target,
+			duration,
+			easing,
+			child: Mutex::new(Some(child)),
+			spatial: OnceLock::new(),
+			inner_key: OnceLock::new(),
+		}
+	}
+
+	fn spatial_key(inner_key: u64) -> u64 {
+		gen_inner_key::<Spatial>(inner_key, 0)
+	}
+	fn spatial(&self, transform: Transform) -> &ElementWrapper<State, Spatial, ((), E)> {
+		self.spatial.get_or_init(|| {
+			let child = self.child.lock().unwrap().take().unwrap();
+			Spatial(transform).build().child(child)
+		})
+	}
+}
+
+impl<State: ValidState, E: Element<State>> ElementDiffer<State> for Slot<State, E> {
+	fn create_inner_recursive(
+		&self,
+		inner_key: u64,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let _ = self.inner_key.set(inner_key);
+		inner_map.insert_raw(inner_key, AnimatedInner::new(&self.target, self.duration));
+		self.spatial(self.target).create_inner_recursive(
+			Self::spatial_key(inner_key),
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+	}
+
+	fn frame_recursive(
+		&self,
+		context: &Context,
+		info: &FrameInfo,
+		state: &mut State,
+		inner_map: &mut ElementInnerMap,
+	) {
+		let moving = self
+			.inner_key
+			.get()
+			.and_then(|&inner_key| inner_map.get_raw_mut::<AnimatedInner<Transform>>(inner_key))
+			.is_some_and(|inner| inner.tick(info.delta, &self.target, self.duration, self.easing));
+		if moving {
+			inner_map.request_update();
+		}
+
+		if let Some(spatial) = self.spatial.get() {
+			spatial.frame_recursive(context, info, state, inner_map);
+		}
+	}
+
+	fn diff_same_type(
+		&self,
+		inner_key: u64,
+		old: &Self,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let _ = self.inner_key.set(inner_key);
+		let (Some(inner), Some(old_spatial)) = (
+			inner_map.get_raw_mut::<AnimatedInner<Transform>>(inner_key),
+			old.spatial.get(),
+		) else {
+			self.create_inner_recursive(
+				inner_key,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			);
+			return;
+		};
+		let current = inner.retarget(&old.target, &self.target, self.duration);
+
+		self.spatial(current).diff_same_type(
+			Self::spatial_key(inner_key),
+			old_spatial,
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+	}
+
+	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
+		if let Some(spatial) = self.spatial.get() {
+			spatial.destroy_inner_recursive(inner_map);
+		}
+		if let Some(&inner_key) = self.inner_key.get() {
+			inner_map.remove(inner_key);
+		}
+	}
+
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		match self.spatial.get() {
+			Some(spatial) => spatial.bounds_hint(),
+			None => {
+				let bounds = self.child.lock().unwrap().as_ref()?.bounds_hint()?;
+				let translation = self.target.translation.map(Vec3::from);
+				Some(BoundingBox {
+					center: (Vec3::from(bounds.center) + translation.unwrap_or_default()).into(),
+					size: bounds.size,
+				})
+			}
+		}
+	}
+
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		if let Some(spatial) = self.spatial.get() {
+			return spatial.blueprint(context, nodes);
+		}
+		let mut children = Vec::new();
+		if let Some(child) = self.child.lock().unwrap().as_ref() {
+			child.blueprint(context, &mut children);
+		}
+		nodes.push(BlueprintNode {
+			element_type: "Spatial".to_string(),
+			debug: format!("{:?}", Spatial(self.target)),
+			children,
+		});
+	}
+}
+
+impl<State: ValidState, E: Element<State>> Element<State> for Slot<State, E> {}
+
+#[tokio::test]
+async fn asteroids_layout_elements() {
+	use crate::{Projector, Reify, elements::Text};
+
+	struct TestState {
+		items: Vec<&'static str>,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Grid::new(2, [0.1, 0.1])
+				.duration(Duration::from_secs(1))
+				.easing(Easing::Linear)
+				.keyed_children(
+					self.items
+						.iter()
+						.map(|item| (*item, Text::new(*item).build())),
+				)
+		}
+	}
+	fn slot(projector: &Projector<TestState>, text: &str) -> [f32; 2] {
+		let scene = projector.scene().unwrap();
+		let text = scene
+			.find("Text")
+			.find(|node| node.debug.contains(text))
+			.unwrap();
+		let translation = scene
+			.get(&text.parent)
+			.unwrap()
+			.transform
+			.unwrap()
+			.translation
+			.unwrap();
+		[translation.x, translation.y]
+	}
+
+	let context = Context::headless().await.unwrap();
+	let info = FrameInfo {
+		delta: 0.5,
+		elapsed: 0.0,
+	};
+	let mut state = TestState {
+		items: vec!["a", "b", "c"],
+	};
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	assert_eq!(slot(&projector, "a"), [-0.05, 0.05]);
+	assert_eq!(slot(&projector, "b"), [0.05, 0.05]);
+	assert_eq!(slot(&projector, "c"), [-0.05, -0.05]);
+
+	// "c" moves up into the first slot over a second instead of jumping
+	state.items.remove(0);
+	state.items.swap(0, 1);
+	projector.update(&context, &mut state);
+	projector.frame(&context, &info, &mut state);
+	projector.update(&context, &mut state);
+	assert_eq!(slot(&projector, "c"), [-0.05, -0.025]);
+	projector.frame(&context, &info, &mut state);
+	projector.update(&context, &mut state);
+	assert_eq!(slot(&projector, "c"), [-0.05, 0.0]);
+	assert_eq!(slot(&projector, "b"), [0.05, 0.0]);
+}
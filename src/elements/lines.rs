@@ -2,10 +2,11 @@ use crate::{
 	Context, CreateInnerInfo, ValidState,
 	custom::{CustomElement, Transformable},
 };
+use glam::Vec3;
 use stardust_xr_fusion::{
 	drawable::{Line, LinesAspect},
 	node::NodeError,
-	spatial::{SpatialRef, Transform},
+	spatial::{BoundingBox, SpatialRef, Transform},
 };
 use std::fmt::Debug;
 
@@ -38,7 +39,13 @@ impl<State: ValidState> CustomElement<State> for Lines {
 		stardust_xr_fusion::drawable::Lines::create(info.parent_space, self.transform, &self.lines)
 	}
 
-	fn diff(&self, old_self: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		self.apply_transform(old_self, inner);
 		if self.lines != old_self.lines {
 			let _ = inner.set_lines(&self.lines);
@@ -47,6 +54,22 @@ impl<State: ValidState> CustomElement<State> for Lines {
 	fn spatial_aspect<'a>(&self, inner: &Self::Inner) -> SpatialRef {
 		inner.clone().as_spatial().as_spatial_ref()
 	}
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		let mut points = self
+			.lines
+			.iter()
+			.flat_map(|line| &line.points)
+			.map(|point| (Vec3::from(point.point), point.thickness / 2.0));
+		let (first, first_radius) = points.next()?;
+		let (min, max) = points.fold(
+			(first - first_radius, first + first_radius),
+			|(min, max), (point, radius)| (min.min(point - radius), max.max(point + radius)),
+		);
+		Some(BoundingBox {
+			center: ((min + max) / 2.0).into(),
+			size: (max - min).into(),
+		})
+	}
 }
 impl Transformable for Lines {
 	fn transform(&self) -> &Transform {
@@ -19,20 +19,25 @@ macro_rules! mod_expose {
 	};
 }
 
+mod_expose!(animated);
 mod_expose!(axes);
 mod_expose!(button);
 mod_expose!(derezzable);
 mod_expose!(dial);
+mod_expose!(error_boundary);
 mod_expose!(field_viz);
 mod_expose!(grabbable);
 mod_expose!(handle);
 mod_expose!(keyboard);
+mod_expose!(layout);
 mod_expose!(lines);
 mod_expose!(model);
 mod_expose!(mouse);
 mod_expose!(panel_ui);
 mod_expose!(playspace);
+mod_expose!(portal);
 mod_expose!(reparentable);
+mod_expose!(resource_scope);
 mod_expose!(pen);
 mod_expose!(sky_light);
 mod_expose!(sky_texture);
@@ -41,4 +46,8 @@ mod_expose!(grab_ring);
 mod_expose!(file_watcher);
 mod_expose!(bounds);
 mod_expose!(spatial);
+mod_expose!(stack);
+mod_expose!(task);
 mod_expose!(text);
+mod_expose!(timer);
+mod_expose!(transition);
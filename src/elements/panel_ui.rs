@@ -1,6 +1,6 @@
 use crate::{
 	Context, CreateInnerInfo, ValidState,
-	custom::{CustomElement, FnWrapper},
+	custom::{CustomElement, FnWrapper, LocalTransform},
 };
 use derive_setters::Setters;
 use stardust_xr_fusion::{
@@ -56,7 +56,14 @@ impl<State: ValidState> CustomElement<State> for PanelUI<State> {
 		Ok((panel_item_ui, info.parent_space.clone()))
 	}
 
-	fn diff(&self, _old_self: &Self, _inner: &mut Self::Inner, _resource: &mut Self::Resource) {}
+	fn diff(
+		&self,
+		_context: &Context,
+		_old_self: &Self,
+		_inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
+	}
 
 	fn frame(
 		&self,
@@ -96,3 +103,5 @@ impl<State: ValidState> CustomElement<State> for PanelUI<State> {
 		inner.1.clone()
 	}
 }
+
+impl<State: ValidState> LocalTransform for PanelUI<State> {}
@@ -1,6 +1,6 @@
 use crate::{
 	Context, CreateInnerInfo, ValidState,
-	custom::{CustomElement, FnWrapper},
+	custom::{CustomElement, FnWrapper, LocalTransform},
 };
 use derive_setters::Setters;
 use glam::{Quat, Vec3};
@@ -100,7 +100,13 @@ impl<State: ValidState> CustomElement<State> for Pen<State> {
 		PenInner::create(info.parent_space, self)
 	}
 
-	fn diff(&self, old: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		if self.thickness != old.thickness || self.length != old.length {
 			_ = inner.visuals.set_lines(&[self.get_lines()]);
 			_ = inner.field.set_shape(Shape::Cylinder(CylinderShape {
@@ -131,6 +137,8 @@ impl<State: ValidState> CustomElement<State> for Pen<State> {
 	}
 }
 
+impl<State: ValidState> LocalTransform for Pen<State> {}
+
 pub struct PenInner {
 	child_root: Spatial,
 	field: Field,
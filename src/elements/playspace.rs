@@ -1,4 +1,7 @@
-use crate::{Context, CreateInnerInfo, ValidState, custom::CustomElement};
+use crate::{
+	Context, CreateInnerInfo, ValidState,
+	custom::{CustomElement, LocalTransform},
+};
 use stardust_xr_fusion::{
 	node::{NodeError, NodeType},
 	spatial::{Spatial, SpatialAspect, SpatialRef, Transform},
@@ -30,12 +33,21 @@ impl<State: ValidState> CustomElement<State> for PlaySpace {
 		});
 		Ok(spatial)
 	}
-	fn diff(&self, _old_self: &Self, _inner: &mut Self::Inner, _resource: &mut Self::Resource) {}
+	fn diff(
+		&self,
+		_context: &Context,
+		_old_self: &Self,
+		_inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
+	}
 	fn spatial_aspect<'a>(&self, inner: &Self::Inner) -> SpatialRef {
 		inner.clone().as_spatial_ref()
 	}
 }
 
+impl LocalTransform for PlaySpace {}
+
 #[tokio::test]
 async fn asteroids_playspace_element() {
 	use crate::{
@@ -0,0 +1,249 @@
+use crate::{
+	Context, CreateInnerInfo, ElementRef, ValidState,
+	custom::{CustomElement, Transformable},
+};
+use derive_setters::Setters;
+use stardust_xr_fusion::{
+	node::{NodeError, NodeType},
+	root::FrameInfo,
+	spatial::{Spatial, SpatialAspect, SpatialRef, Transform},
+};
+use std::fmt::Debug;
+use tokio::task::AbortHandle;
+
+/// Where a [`Portal`] puts its children.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortalTarget {
+	/// The client's root, e.g. for a HUD
+	Root,
+	/// The play space, e.g. for a menu anchored to the floor. Sits at the client root until the play space is known.
+	PlaySpace,
+	/// Whatever element the ref is attached to, e.g. for a tooltip next to something else in the tree.
+	/// Sits under the portal's parent until the ref gets attached.
+	Ref(ElementRef),
+}
+
+/// Parents its children under a different spatial than its parent's,
+/// while they stay in the same state and callback tree.
+#[derive(Debug, Clone, PartialEq, Setters)]
+#[setters(into)]
+pub struct Portal {
+	#[setters(skip)]
+	target: PortalTarget,
+	/// Relative to the target
+	transform: Transform,
+}
+impl Portal {
+	pub fn new(target: PortalTarget) -> Self {
+		Portal {
+			target,
+			transform: Transform::identity(),
+		}
+	}
+	pub fn root() -> Self {
+		Self::new(PortalTarget::Root)
+	}
+	pub fn play_space() -> Self {
+		Self::new(PortalTarget::PlaySpace)
+	}
+	pub fn element(element_ref: ElementRef) -> Self {
+		Self::new(PortalTarget::Ref(element_ref))
+	}
+}
+impl<State: ValidState> CustomElement<State> for Portal {
+	type Inner = PortalInner;
+	type Resource = ();
+	type Error = NodeError;
+
+	fn create_inner(
+		&self,
+		_context: &Context,
+		info: CreateInnerInfo,
+		_resource: &mut Self::Resource,
+	) -> Result<Self::Inner, Self::Error> {
+		let client = info.parent_space.client().clone();
+		let root = client.get_root().clone().as_spatial_ref();
+		let (parent, attached_to) = match &self.target {
+			PortalTarget::Root | PortalTarget::PlaySpace => (root, None),
+			PortalTarget::Ref(element_ref) => match element_ref.spatial() {
+				Some(spatial) => (spatial, element_ref.inner_key()),
+				None => (info.parent_space.clone(), None),
+			},
+		};
+		let spatial = Spatial::create(&parent, self.transform)?;
+
+		let play_space_task = (self.target == PortalTarget::PlaySpace).then(|| {
+			let spatial = spatial.clone();
+			tokio::spawn(async move {
+				if let Some(play_space) = stardust_xr_fusion::objects::play_space(&client).await {
+					let _ = spatial.set_spatial_parent(&play_space.spatial);
+				}
+			})
+			.abort_handle()
+		});
+		Ok(PortalInner {
+			spatial,
+			parent_space: info.parent_space.clone(),
+			attached_to,
+			play_space_task,
+		})
+	}
+	fn diff(
+		&self,
+		_context: &Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
+		self.apply_transform(old_self, &inner.spatial);
+	}
+	fn needs_recreate(&self, old_self: &Self) -> bool {
+		self.target != old_self.target
+	}
+	fn frame(
+		&self,
+		_context: &Context,
+		_info: &FrameInfo,
+		_state: &mut State,
+		inner: &mut Self::Inner,
+	) {
+		let PortalTarget::Ref(element_ref) = &self.target else {
+			return;
+		};
+		// the target may have been created after us, replaced or destroyed since
+		match retarget(
+			inner.attached_to,
+			element_ref.inner_key(),
+			element_ref.spatial(),
+		) {
+			Retarget::Stay => (),
+			Retarget::Attach(spatial, target_key) => {
+				let _ = inner.spatial.set_spatial_parent(&spatial);
+				inner.attached_to = Some(target_key);
+			}
+			Retarget::Detach => {
+				let _ = inner.spatial.set_spatial_parent(&inner.parent_space);
+				inner.attached_to = None;
+			}
+		}
+	}
+	fn spatial_aspect(&self, inner: &Self::Inner) -> SpatialRef {
+		inner.spatial.clone().as_spatial_ref()
+	}
+}
+impl Transformable for Portal {
+	fn transform(&self) -> &Transform {
+		&self.transform
+	}
+	fn transform_mut(&mut self) -> &mut Transform {
+		&mut self.transform
+	}
+}
+
+pub struct PortalInner {
+	spatial: Spatial,
+	/// The portal's own parent, to fall back to when the element ref target goes away
+	parent_space: SpatialRef,
+	/// Inner key of the element ref target we're parented under
+	attached_to: Option<u64>,
+	play_space_task: Option<AbortHandle>,
+}
+impl Drop for PortalInner {
+	fn drop(&mut self) {
+		if let Some(task) = &self.play_space_task {
+			task.abort();
+		}
+	}
+}
+
+/// Where a [`PortalTarget::Ref`] portal should move this frame.
+#[derive(Debug, Clone, PartialEq)]
+enum Retarget<S> {
+	Stay,
+	/// Under the spatial of the element with this inner key
+	Attach(S, u64),
+	/// Back under the portal's parent, the element it was attached to is gone
+	Detach,
+}
+fn retarget<S>(
+	attached_to: Option<u64>,
+	target_key: Option<u64>,
+	target: Option<S>,
+) -> Retarget<S> {
+	if target_key == attached_to {
+		return Retarget::Stay;
+	}
+	match (target_key, target) {
+		(Some(target_key), Some(spatial)) => Retarget::Attach(spatial, target_key),
+		// don't leave the children under a dead node
+		_ if attached_to.is_some() => Retarget::Detach,
+		_ => Retarget::Stay,
+	}
+}
+
+#[tokio::test]
+#[ignore = "interactive demo, needs a running Stardust server"]
+async fn asteroids_portal_element() {
+	use crate::{
+		Element,
+		client::{self, ClientState},
+		elements::{Lines, Text, circle},
+	};
+	use serde::{Deserialize, Serialize};
+
+	#[derive(Default, Serialize, Deserialize)]
+	struct TestState {
+		anchor: ElementRef,
+	}
+	impl crate::util::Migrate for TestState {
+		type Old = Self;
+	}
+	impl ClientState for TestState {
+		const APP_ID: &'static str = "org.asteroids.portal";
+	}
+	impl crate::Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Text::new("anchor")
+				.pos([0.0, 0.5, 0.0])
+				.build()
+				.with_ref(self.anchor.clone())
+				.child(
+					Portal::play_space()
+						.build()
+						.child(Lines::new([circle(16, 0.0, 0.5)]).build()),
+				)
+				.child(
+					Portal::root()
+						.pos([0.0, 0.0, -0.5])
+						.build()
+						.child(Text::new("hud").build()),
+				)
+				.child(
+					Portal::element(self.anchor.clone())
+						.pos([0.0, 0.1, 0.0])
+						.build()
+						.child(Text::new("tooltip").build()),
+				)
+		}
+	}
+
+	client::run::<TestState>(&[]).await
+}
+
+#[tokio::test]
+async fn asteroids_portal_retarget() {
+	// attaches once the ref does
+	assert_eq!(retarget::<&str>(None, None, None), Retarget::Stay);
+	assert_eq!(retarget(None, Some(1), Some("a")), Retarget::Attach("a", 1));
+	assert_eq!(retarget(Some(1), Some(1), Some("a")), Retarget::Stay);
+	// a replaced target moves it over
+	assert_eq!(
+		retarget(Some(1), Some(2), Some("b")),
+		Retarget::Attach("b", 2)
+	);
+	// a destroyed target sends it back to the portal's parent
+	assert_eq!(retarget::<&str>(Some(2), None, None), Retarget::Detach);
+	// a target that failed to create has no spatial to attach to
+	assert_eq!(retarget::<&str>(None, Some(3), None), Retarget::Stay);
+	assert_eq!(retarget::<&str>(Some(2), Some(3), None), Retarget::Detach);
+}
@@ -1,4 +1,7 @@
-use crate::{Context, CreateInnerInfo, ValidState, custom::CustomElement};
+use crate::{
+	Context, CreateInnerInfo, ValidState,
+	custom::{CustomElement, LocalTransform},
+};
 use derive_setters::Setters;
 use stardust_xr_fusion::{
 	node::NodeError,
@@ -37,7 +40,13 @@ impl<State: ValidState> CustomElement<State> for Reparentable {
 			reparentable: None,
 		})
 	}
-	fn diff(&self, _old_self: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		_old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		if self.enabled {
 			inner.enable();
 		} else {
@@ -48,6 +57,8 @@ impl<State: ValidState> CustomElement<State> for Reparentable {
 		inner.spatial()
 	}
 }
+
+impl LocalTransform for Reparentable {}
 pub struct ReparentableInner {
 	connection: Connection,
 	outer_spatial: SpatialRef,
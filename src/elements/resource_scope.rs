@@ -0,0 +1,204 @@
+use crate::{
+	Context, Element, ValidState,
+	blueprint::BlueprintNode,
+	element::{ElementDiffer, ParentSpace},
+	gen_inner_key,
+	inner::ElementInnerMap,
+	resource::ResourceRegistry,
+};
+use stardust_xr_fusion::{root::FrameInfo, spatial::BoundingBox};
+use std::{
+	any::TypeId,
+	hash::{DefaultHasher, Hash, Hasher},
+	marker::PhantomData,
+	path::Path,
+};
+
+/// Keeps scope keys from colliding with inner keys
+struct ScopeKey;
+
+/// Gives the elements inside `content` their own `CustomElement::Resource`s instead of the ones shared by the whole tree,
+/// e.g. a cache that should go away with the subtree using it.
+///
+/// Resources are still one per element type within a scope, and get dropped once the last element using them is destroyed.
+pub struct ResourceScope<State: ValidState, C: Element<State>> {
+	shared: Option<u64>,
+	content: C,
+	phantom: PhantomData<State>,
+}
+impl<State: ValidState, C: Element<State>> ResourceScope<State, C> {
+	pub fn new(content: C) -> Self {
+		ResourceScope {
+			shared: None,
+			content,
+			phantom: PhantomData,
+		}
+	}
+	/// Share resources with every other scope using the same `key`, wherever it is in the tree.
+	pub fn shared(mut self, key: impl Hash) -> Self {
+		let mut hasher = DefaultHasher::new();
+		TypeId::of::<ScopeKey>().hash(&mut hasher);
+		key.hash(&mut hasher);
+		self.shared = Some(hasher.finish());
+		self
+	}
+
+	fn scope(&self, inner_key: u64) -> u64 {
+		self.shared
+			.unwrap_or_else(|| gen_inner_key::<ScopeKey>(inner_key, 0))
+	}
+}
+
+impl<State: ValidState, C: Element<State>> ElementDiffer<State> for ResourceScope<State, C> {
+	fn create_inner_recursive(
+		&self,
+		inner_key: u64,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		resources.push_scope(self.scope(inner_key));
+		self.content.create_inner_recursive(
+			inner_key,
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+		resources.pop_scope();
+	}
+
+	fn frame_recursive(
+		&self,
+		context: &Context,
+		info: &FrameInfo,
+		state: &mut State,
+		inner_map: &mut ElementInnerMap,
+	) {
+		self.content
+			.frame_recursive(context, info, state, inner_map);
+	}
+
+	fn diff_same_type(
+		&self,
+		inner_key: u64,
+		old: &Self,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		// elements keep the resource they were created with, the scope only matters for new ones
+		resources.push_scope(self.scope(inner_key));
+		self.content.diff_same_type(
+			inner_key,
+			&old.content,
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+		resources.pop_scope();
+	}
+
+	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
+		self.content.destroy_inner_recursive(inner_map);
+	}
+
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		self.content.bounds_hint()
+	}
+
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		self.content.blueprint(context, nodes);
+	}
+}
+
+impl<State: ValidState, C: Element<State>> Element<State> for ResourceScope<State, C> {}
+
+#[tokio::test]
+async fn asteroids_resource_scope() {
+	use crate::{
+		CreateInnerInfo, LocalTransform, Projector, Reify, custom::CustomElement, elements::Spatial,
+	};
+	use stardust_xr_fusion::{node::NodeError, spatial::SpatialRef};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+	#[derive(Default)]
+	struct Cache;
+	impl Drop for Cache {
+		fn drop(&mut self) {
+			DROPS.fetch_add(1, Ordering::Relaxed);
+		}
+	}
+
+	#[derive(Debug)]
+	struct Cached;
+	impl<State: ValidState> CustomElement<State> for Cached {
+		type Inner = stardust_xr_fusion::spatial::Spatial;
+		type Resource = Cache;
+		type Error = NodeError;
+
+		fn create_inner(
+			&self,
+			_context: &Context,
+			info: CreateInnerInfo,
+			_resource: &mut Self::Resource,
+		) -> Result<Self::Inner, Self::Error> {
+			stardust_xr_fusion::spatial::Spatial::create(
+				info.parent_space,
+				stardust_xr_fusion::spatial::Transform::none(),
+			)
+		}
+		fn diff(
+			&self,
+			_context: &Context,
+			_old_self: &Self,
+			_inner: &mut Self::Inner,
+			_resource: &mut Self::Resource,
+		) {
+		}
+		fn spatial_aspect(&self, inner: &Self::Inner) -> SpatialRef {
+			inner.clone().as_spatial_ref()
+		}
+	}
+	impl LocalTransform for Cached {}
+
+	struct TestState {
+		scoped: usize,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Spatial::default().build().child(ResourceScope::new(
+				Spatial::default()
+					.build()
+					.children((0..self.scoped).map(|_| Cached.build())),
+			))
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let mut state = TestState { scoped: 2 };
+	let mut projector = Projector::headless(&state, &context, "/".into());
+
+	// one of the 2 users is left, the scope's cache stays
+	state.scoped = 1;
+	projector.update(&context, &mut state);
+	assert_eq!(DROPS.load(Ordering::Relaxed), 0);
+
+	state.scoped = 0;
+	projector.update(&context, &mut state);
+	assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+
+	state.scoped = 1;
+	projector.update(&context, &mut state);
+	drop(projector);
+	assert_eq!(DROPS.load(Ordering::Relaxed), 2);
+}
@@ -5,7 +5,10 @@ use stardust_xr_fusion::{
 	values::ResourceID,
 };
 
-use crate::{Context, CreateInnerInfo, ValidState, custom::CustomElement};
+use crate::{
+	Context, CreateInnerInfo, ValidState,
+	custom::{CustomElement, LocalTransform},
+};
 
 #[derive(Debug)]
 pub struct SkyLight(pub ResourceID);
@@ -26,7 +29,13 @@ impl<State: ValidState> CustomElement<State> for SkyLight {
 		Ok(SkyLightInner(info.parent_space.clone()))
 	}
 
-	fn diff(&self, old_self: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		if self.0 != old_self.0 {
 			_ = set_sky_light(inner.0.client(), Some(&self.0));
 		}
@@ -36,6 +45,8 @@ impl<State: ValidState> CustomElement<State> for SkyLight {
 		inner.0.clone()
 	}
 }
+
+impl LocalTransform for SkyLight {}
 pub struct SkyLightInner(SpatialRef);
 impl Drop for SkyLightInner {
 	fn drop(&mut self) {
@@ -5,7 +5,10 @@ use stardust_xr_fusion::{
 	values::ResourceID,
 };
 
-use crate::{Context, CreateInnerInfo, ValidState, custom::CustomElement};
+use crate::{
+	Context, CreateInnerInfo, ValidState,
+	custom::{CustomElement, LocalTransform},
+};
 
 #[derive(Debug)]
 pub struct SkyTexture(pub ResourceID);
@@ -26,7 +29,13 @@ impl<State: ValidState> CustomElement<State> for SkyTexture {
 		Ok(SkyTexInner(info.parent_space.clone()))
 	}
 
-	fn diff(&self, old_self: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		if self.0 != old_self.0 {
 			_ = set_sky_tex(inner.0.client(), Some(&self.0));
 		}
@@ -36,6 +45,8 @@ impl<State: ValidState> CustomElement<State> for SkyTexture {
 		inner.0.clone()
 	}
 }
+
+impl LocalTransform for SkyTexture {}
 pub struct SkyTexInner(SpatialRef);
 impl Drop for SkyTexInner {
 	fn drop(&mut self) {
@@ -0,0 +1,407 @@
+use crate::{
+	Context, Element, ValidState,
+	blueprint::BlueprintNode,
+	custom::{CustomElement, Transformable},
+	element::{ElementDiffer, ElementWrapper, ParentSpace},
+	elements::Spatial,
+	inner::ElementInnerMap,
+	resource::ResourceRegistry,
+};
+use glam::Vec3;
+use mint::Vector2;
+use stardust_xr_fusion::{
+	root::FrameInfo,
+	spatial::{BoundingBox, Transform},
+};
+use std::{marker::PhantomData, path::Path};
+
+/// Direction a [`Stack`] lays its children out in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+	/// Left to right along +X
+	Row,
+	/// Top to bottom along -Y
+	Column,
+}
+
+/// Where children sit across a [`Stack`]'s axis, relative to its largest child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+	/// Top of a row, left of a column
+	#[default]
+	Start,
+	Center,
+	/// Bottom of a row, right of a column
+	End,
+}
+impl Align {
+	/// How far past the start a child of `size` goes in a line `available` wide
+	fn offset(self, size: f32, available: f32) -> f32 {
+		match self {
+			Align::Start => 0.0,
+			Align::Center => (available - size) / 2.0,
+			Align::End => available - size,
+		}
+	}
+}
+
+/// Places children one after another along an axis using their bounds,
+/// so lists and toolbars don't need hand-computed positions.
+///
+/// The stack's origin is its top left corner and its bounds hint includes the padding,
+/// so stacks can be nested. Children are laid out in [`Stack::build`], so the setters work in any order.
+///
+/// Each child is measured with its bounds hint (e.g. `Button` size or `Text` character height),
+/// use [`Stack::child_sized`] for anything without one.
+/// Elements that build their subtree lazily like `Memo`, `Animated` and `Transition` have none,
+/// measuring them would run their builders on every reify.
+pub struct Stack<State: ValidState, C: ElementDiffer<State>> {
+	axis: Axis,
+	gap: f32,
+	padding: f32,
+	align: Align,
+	transform: Transform,
+	/// Bounds of every child so far, in the order they were added
+	slots: Vec<Option<BoundingBox>>,
+	children: C,
+	phantom: PhantomData<State>,
+}
+impl<State: ValidState> Stack<State, ()> {
+	pub fn new(axis: Axis) -> Self {
+		Stack {
+			axis,
+			gap: 0.0,
+			padding: 0.0,
+			align: Align::default(),
+			transform: Transform::none(),
+			slots: Vec::new(),
+			children: (),
+			phantom: PhantomData,
+		}
+	}
+	pub fn row() -> Self {
+		Self::new(Axis::Row)
+	}
+	pub fn column() -> Self {
+		Self::new(Axis::Column)
+	}
+}
+impl<State: ValidState, C: StackChildren<State>> Stack<State, C> {
+	/// Space between children along the axis
+	pub fn gap(mut self, gap: f32) -> Self {
+		self.gap = gap;
+		self
+	}
+	/// Space between the stack's edges and its children
+	pub fn padding(mut self, padding: f32) -> Self {
+		self.padding = padding;
+		self
+	}
+	/// Alignment across the axis
+	pub fn align(mut self, align: Align) -> Self {
+		self.align = align;
+		self
+	}
+	pub fn child<E: Element<State>>(
+		self,
+		child: E,
+	) -> Stack<State, (C, ElementWrapper<State, Spatial, ((), E)>)> {
+		let bounds = child.bounds_hint();
+		self.place(bounds, child)
+	}
+	/// Add a child with an explicit `[width, height]`, centered on its origin
+	pub fn child_sized<E: Element<State>>(
+		self,
+		size: impl Into<Vector2<f32>>,
+		child: E,
+	) -> Stack<State, (C, ElementWrapper<State, Spatial, ((), E)>)> {
+		let size = size.into();
+		self.place(
+			Some(BoundingBox {
+				center: [0.0; 3].into(),
+				size: [size.x, size.y, 0.0].into(),
+			}),
+			child,
+		)
+	}
+	pub fn children<E: Element<State>>(
+		mut self,
+		children: impl IntoIterator<Item = E>,
+	) -> Stack<State, (C, Vec<ElementWrapper<State, Spatial, ((), E)>>)> {
+		let children = children
+			.into_iter()
+			.map(|child| {
+				self.slots.push(child.bounds_hint());
+				Spatial::default().build().child(child)
+			})
+			.collect();
+		self.push(children)
+	}
+	pub fn build(mut self) -> ElementWrapper<State, Spatial, (C, StackExtent)> {
+		let (positions, extent) = self.layout();
+		self.children.place(&mut positions.into_iter());
+		Spatial(self.transform)
+			.build()
+			.replace_children((self.children, StackExtent(extent)))
+	}
+
+	fn place<E: Element<State>>(
+		mut self,
+		bounds: Option<BoundingBox>,
+		child: E,
+	) -> Stack<State, (C, ElementWrapper<State, Spatial, ((), E)>)> {
+		self.slots.push(bounds);
+		self.push(Spatial::default().build().child(child))
+	}
+	fn push<N: ElementDiffer<State>>(self, next: N) -> Stack<State, (C, N)> {
+		Stack {
+			axis: self.axis,
+			gap: self.gap,
+			padding: self.padding,
+			align: self.align,
+			transform: self.transform,
+			slots: self.slots,
+			children: (self.children, next),
+			phantom: PhantomData,
+		}
+	}
+	/// Position of each slot's origin, and the bounds of the whole stack
+	fn layout(&self) -> (Vec<[f32; 2]>, BoundingBox) {
+		// elements with no size still get a slot so the gaps stay consistent
+		let slots = self
+			.slots
+			.iter()
+			.map(|bounds| {
+				bounds.as_ref().map_or((Vec3::ZERO, Vec3::ZERO), |bounds| {
+					(bounds.center.into(), bounds.size.into())
+				})
+			})
+			.collect::<Vec<(Vec3, Vec3)>>();
+		let cross_size = slots
+			.iter()
+			.map(|(_, size)| match self.axis {
+				Axis::Row => size.y,
+				Axis::Column => size.x,
+			})
+			.fold(0.0, f32::max);
+
+		let mut cursor = self.padding;
+		let positions = slots
+			.iter()
+			.map(|(center, size)| {
+				let (x, y, main_size) = match self.axis {
+					Axis::Row => {
+						let x = cursor - (center.x - size.x / 2.0);
+						let top = -self.padding - self.align.offset(size.y, cross_size);
+						(x, top - (center.y + size.y / 2.0), size.x)
+					}
+					Axis::Column => {
+						let y = -cursor - (center.y + size.y / 2.0);
+						let left = self.padding + self.align.offset(size.x, cross_size);
+						(left - (center.x - size.x / 2.0), y, size.y)
+					}
+				};
+				cursor += main_size + self.gap;
+				[x, y]
+			})
+			.collect();
+
+		// the cursor ends one gap past the last child
+		let main_size = match slots.is_empty() {
+			true => self.padding * 2.0,
+			false => cursor - self.gap + self.padding,
+		};
+		let cross_size = cross_size + self.padding * 2.0;
+		let (width, height) = match self.axis {
+			Axis::Row => (main_size, cross_size),
+			Axis::Column => (cross_size, main_size),
+		};
+		let extent = BoundingBox {
+			center: [width / 2.0, -height / 2.0, 0.0].into(),
+			size: [width, height, 0.0].into(),
+		};
+		(positions, extent)
+	}
+}
+impl<State: ValidState, C: ElementDiffer<State>> Transformable for Stack<State, C> {
+	fn transform(&self) -> &Transform {
+		&self.transform
+	}
+	fn transform_mut(&mut self) -> &mut Transform {
+		&mut self.transform
+	}
+}
+
+/// The slots added to a [`Stack`], moved into place once all of them are known.
+pub(crate) trait StackChildren<State: ValidState>: ElementDiffer<State> {
+	/// Move each slot to the next position, in the order they were added
+	fn place(&mut self, positions: &mut impl Iterator<Item = [f32; 2]>);
+}
+impl<State: ValidState> StackChildren<State> for () {
+	fn place(&mut self, _positions: &mut impl Iterator<Item = [f32; 2]>) {}
+}
+impl<State: ValidState, C: StackChildren<State>, E: Element<State>> StackChildren<State>
+	for (C, ElementWrapper<State, Spatial, ((), E)>)
+{
+	fn place(&mut self, positions: &mut impl Iterator<Item = [f32; 2]>) {
+		self.0.place(positions);
+		place_slot(&mut self.1, positions);
+	}
+}
+impl<State: ValidState, C: StackChildren<State>, E: Element<State>> StackChildren<State>
+	for (C, Vec<ElementWrapper<State, Spatial, ((), E)>>)
+{
+	fn place(&mut self, positions: &mut impl Iterator<Item = [f32; 2]>) {
+		self.0.place(positions);
+		for slot in &mut self.1 {
+			place_slot(slot, positions);
+		}
+	}
+}
+fn place_slot<State: ValidState, E: Element<State>>(
+	slot: &mut ElementWrapper<State, Spatial, ((), E)>,
+	positions: &mut impl Iterator<Item = [f32; 2]>,
+) {
+	if let (Some(spatial), Some([x, y])) = (&mut slot.custom_element, positions.next()) {
+		spatial.0.translation = Some([x, y, 0.0].into());
+	}
+}
+
+/// Takes up a [`Stack`]'s whole area including its padding in its bounds hint, nothing on the server.
+pub struct StackExtent(BoundingBox);
+impl<State: ValidState> ElementDiffer<State> for StackExtent {
+	fn create_inner_recursive(
+		&self,
+		_inner_key: u64,
+		_context: &Context,
+		_parent_space: &ParentSpace,
+		_element_path: &Path,
+		_inner_map: &mut ElementInnerMap,
+		_resources: &mut ResourceRegistry,
+	) {
+	}
+	fn frame_recursive(
+		&self,
+		_context: &Context,
+		_info: &FrameInfo,
+		_state: &mut State,
+		_inner_map: &mut ElementInnerMap,
+	) {
+	}
+	fn diff_same_type(
+		&self,
+		_inner_key: u64,
+		_old: &Self,
+		_context: &Context,
+		_parent_space: &ParentSpace,
+		_element_path: &Path,
+		_inner_map: &mut ElementInnerMap,
+		_resources: &mut ResourceRegistry,
+	) {
+	}
+	fn destroy_inner_recursive(&self, _inner_map: &mut ElementInnerMap) {}
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		Some(self.0.clone())
+	}
+	fn blueprint(&self, _context: &Context, _nodes: &mut Vec<BlueprintNode>) {}
+}
+
+#[tokio::test]
+async fn asteroids_stack_element() {
+	use crate::{Context, Projector, Reify, elements::Button};
+
+	struct TestState;
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Stack::column()
+				.gap(0.01)
+				.child(Button::new(|_: &mut Self| {}).size([0.1, 0.02]).build())
+				.child(Button::new(|_: &mut Self| {}).size([0.05, 0.04]).build())
+				.build()
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let projector = Projector::headless(&TestState, &context, "/".into());
+	let scene = projector.scene().unwrap();
+	let mut positions = scene
+		.find("Button")
+		.map(|button| {
+			let slot = scene.get(&button.parent).unwrap();
+			let translation = slot.transform.unwrap().translation.unwrap();
+			[translation.x, translation.y]
+		})
+		.collect::<Vec<_>>();
+	positions.sort_by(|a, b| b[1].total_cmp(&a[1]));
+	// each button's top left corner sits right under the last one
+	let expected = [[0.05, -0.01], [0.025, -0.05]];
+	for (position, expected) in positions.iter().zip(expected) {
+		assert!((position[0] - expected[0]).abs() < 1e-6);
+		assert!((position[1] - expected[1]).abs() < 1e-6);
+	}
+	assert_eq!(positions.len(), 2);
+}
+
+#[tokio::test]
+async fn asteroids_stack_align() {
+	use crate::{Context, Projector, Reify, elements::Button};
+
+	struct TestState(Align);
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			// set after the children, the narrow button still lines up with the wide one
+			Stack::column()
+				.child(Button::new(|_: &mut Self| {}).size([0.1, 0.02]).build())
+				.child(Button::new(|_: &mut Self| {}).size([0.05, 0.04]).build())
+				.align(self.0)
+				.build()
+		}
+	}
+	async fn x_positions(align: Align) -> Vec<f32> {
+		let context = Context::headless().await.unwrap();
+		let projector = Projector::headless(&TestState(align), &context, "/".into());
+		let scene = projector.scene().unwrap();
+		let mut positions = scene
+			.find("Button")
+			.map(|button| {
+				let slot = scene.get(&button.parent).unwrap();
+				slot.transform.unwrap().translation.unwrap()
+			})
+			.collect::<Vec<_>>();
+		positions.sort_by(|a, b| b.y.total_cmp(&a.y));
+		positions.into_iter().map(|position| position.x).collect()
+	}
+
+	for (align, expected) in [
+		(Align::Start, [0.05, 0.025]),
+		(Align::Center, [0.05, 0.05]),
+		(Align::End, [0.05, 0.075]),
+	] {
+		let positions = x_positions(align).await;
+		assert_eq!(positions.len(), 2);
+		for (position, expected) in positions.iter().zip(expected) {
+			assert!((position - expected).abs() < 1e-6, "{align:?}");
+		}
+	}
+}
+
+#[tokio::test]
+async fn asteroids_stack_padding_bounds() {
+	use crate::elements::Button;
+
+	struct TestState;
+	let stack = Stack::column()
+		.child(
+			Button::new(|_: &mut TestState| {})
+				.size([0.1, 0.02])
+				.build(),
+		)
+		.padding(0.01)
+		.build();
+	// the padding on both sides counts, so a parent stack can fit it
+	let bounds = stack.bounds_hint().unwrap();
+	assert!((bounds.size.x - 0.12).abs() < 1e-6);
+	assert!((bounds.size.y - 0.04).abs() < 1e-6);
+	assert!((bounds.center.x - 0.06).abs() < 1e-6);
+	assert!((bounds.center.y + 0.02).abs() < 1e-6);
+}
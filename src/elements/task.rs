@@ -0,0 +1,220 @@
+use crate::{
+	Context, CreateInnerInfo, ValidState,
+	custom::{CustomElement, FnWrapper, LocalTransform},
+};
+use derive_where::derive_where;
+use futures_util::{FutureExt, future::BoxFuture};
+use stardust_xr_fusion::{root::FrameInfo, spatial::SpatialRef};
+use std::{convert::Infallible, fmt::Debug, future::Future};
+use tokio::{sync::oneshot, task::AbortHandle};
+
+pub struct TaskInner<T> {
+	spatial: SpatialRef,
+	running: RunningTask<T>,
+}
+
+/// The spawned future, aborted when dropped
+struct RunningTask<T> {
+	task: AbortHandle,
+	result_rx: oneshot::Receiver<T>,
+}
+impl<T> Drop for RunningTask<T> {
+	fn drop(&mut self) {
+		self.task.abort();
+	}
+}
+
+type FutureFactory<I, T> = dyn Fn(I) -> BoxFuture<'static, T> + Send + Sync;
+
+/// Runs the future made from `input` and hands its output to `on_complete` on the next frame.
+///
+/// When `input` changes the running future is aborted and a new one is started,
+/// and it's aborted when the element is destroyed.
+#[derive_where(Debug; I)]
+pub struct Task<
+	State: ValidState,
+	I: Debug + PartialEq + Clone + Send + Sync + 'static,
+	T: Send + 'static,
+> {
+	input: I,
+	future: FnWrapper<FutureFactory<I, T>>,
+	on_complete: FnWrapper<dyn Fn(&mut State, T) + Send + Sync>,
+}
+impl<State: ValidState, I: Debug + PartialEq + Clone + Send + Sync + 'static, T: Send + 'static>
+	Task<State, I, T>
+{
+	pub fn new<Fut: Future<Output = T> + Send + 'static>(
+		input: I,
+		future: impl Fn(I) -> Fut + Send + Sync + 'static,
+		on_complete: impl Fn(&mut State, T) + Send + Sync + 'static,
+	) -> Self {
+		Task {
+			input,
+			future: FnWrapper(Box::new(move |input| future(input).boxed())),
+			on_complete: FnWrapper(Box::new(on_complete)),
+		}
+	}
+
+	fn spawn(&self) -> RunningTask<T> {
+		let (result_tx, result_rx) = oneshot::channel();
+		let future = (self.future.0)(self.input.clone());
+		let task = tokio::spawn(async move {
+			let _ = result_tx.send(future.await);
+		})
+		.abort_handle();
+		RunningTask { task, result_rx }
+	}
+	/// Hand the output to `on_complete` if the future finished
+	fn poll_complete(&self, state: &mut State, running: &mut RunningTask<T>) {
+		if let Ok(result) = running.result_rx.try_recv() {
+			(self.on_complete.0)(state, result);
+		}
+	}
+}
+impl<State: ValidState, I: Debug + PartialEq + Clone + Send + Sync + 'static, T: Send + 'static>
+	CustomElement<State> for Task<State, I, T>
+{
+	type Inner = TaskInner<T>;
+	type Resource = ();
+	type Error = Infallible;
+
+	fn create_inner(
+		&self,
+		_context: &Context,
+		info: CreateInnerInfo,
+		_resource: &mut Self::Resource,
+	) -> Result<Self::Inner, Self::Error> {
+		Ok(TaskInner {
+			spatial: info.parent_space.clone(),
+			running: self.spawn(),
+		})
+	}
+
+	fn diff(
+		&self,
+		_context: &Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
+		if old_self.input != self.input {
+			// dropping the old one aborts the old task
+			inner.running = self.spawn();
+		}
+	}
+
+	fn frame(
+		&self,
+		_context: &Context,
+		_info: &FrameInfo,
+		state: &mut State,
+		inner: &mut Self::Inner,
+	) {
+		self.poll_complete(state, &mut inner.running);
+	}
+
+	fn spatial_aspect(&self, inner: &Self::Inner) -> SpatialRef {
+		inner.spatial.clone()
+	}
+}
+
+impl<State: ValidState, I: Debug + PartialEq + Clone + Send + Sync + 'static, T: Send + 'static>
+	LocalTransform for Task<State, I, T>
+{
+}
+
+#[tokio::test]
+#[ignore = "interactive demo, needs a running Stardust server"]
+async fn asteroids_task_element() {
+	use crate::{
+		client::{self, ClientState},
+		elements::Text,
+	};
+	use serde::{Deserialize, Serialize};
+	use std::time::Duration;
+
+	#[derive(Default, Serialize, Deserialize)]
+	struct TestState {
+		delay_ms: u64,
+		waited: Option<u64>,
+	}
+	impl crate::util::Migrate for TestState {
+		type Old = Self;
+	}
+	impl ClientState for TestState {
+		const APP_ID: &'static str = "org.asteroids.task";
+	}
+	impl crate::Reify for TestState {
+		fn reify(&self) -> impl crate::Element<Self> {
+			Task::new(
+				self.delay_ms.max(500),
+				|delay_ms| async move {
+					tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+					delay_ms
+				},
+				|state: &mut Self, waited| {
+					state.waited = Some(waited);
+					// restarts the task with a new input
+					state.delay_ms = waited + 500;
+				},
+			)
+			.build()
+			.child(
+				Text::new(match self.waited {
+					Some(waited) => format!("waited {waited}ms"),
+					None => "waiting...".to_string(),
+				})
+				.build(),
+			)
+		}
+	}
+
+	client::run::<TestState>(&[]).await
+}
+
+#[tokio::test]
+async fn asteroids_task_completion() {
+	use std::time::Duration;
+
+	#[derive(Default)]
+	struct TestState {
+		doubled: Option<u32>,
+	}
+
+	let task = Task::new(
+		21,
+		|input: u32| async move {
+			tokio::task::yield_now().await;
+			input * 2
+		},
+		|state: &mut TestState, doubled| state.doubled = Some(doubled),
+	);
+	let mut state = TestState::default();
+	let mut running = task.spawn();
+	tokio::time::timeout(Duration::from_secs(1), async {
+		while state.doubled.is_none() {
+			tokio::task::yield_now().await;
+			task.poll_complete(&mut state, &mut running);
+		}
+	})
+	.await
+	.unwrap();
+	assert_eq!(state.doubled, Some(42));
+
+	// replacing or destroying the inner aborts an unfinished task
+	let pending = Task::new(
+		(),
+		|_| std::future::pending::<()>(),
+		|_: &mut TestState, _| {},
+	);
+	let running = pending.spawn();
+	let handle = running.task.clone();
+	drop(running);
+	tokio::time::timeout(Duration::from_secs(1), async {
+		while !handle.is_finished() {
+			tokio::task::yield_now().await;
+		}
+	})
+	.await
+	.unwrap();
+}
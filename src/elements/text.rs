@@ -6,7 +6,7 @@ use derive_setters::Setters;
 use stardust_xr_fusion::{
 	drawable::{TextAspect, TextBounds, TextStyle, XAlign, YAlign},
 	node::NodeError,
-	spatial::{SpatialRef, Transform},
+	spatial::{BoundingBox, SpatialRef, Transform},
 	values::color::rgba_linear,
 	values::{Color, ResourceID},
 };
@@ -64,7 +64,13 @@ impl<State: ValidState> CustomElement<State> for Text {
 			},
 		)
 	}
-	fn diff(&self, old_self: &Self, inner: &mut Self::Inner, _resource: &mut Self::Resource) {
+	fn diff(
+		&self,
+		_context: &Context,
+		old_self: &Self,
+		inner: &mut Self::Inner,
+		_resource: &mut Self::Resource,
+	) {
 		self.apply_transform(old_self, inner);
 		if self.text != old_self.text {
 			let _ = inner.set_text(&self.text);
@@ -73,9 +79,45 @@ impl<State: ValidState> CustomElement<State> for Text {
 			let _ = inner.set_character_height(self.character_height);
 		}
 	}
+	fn needs_recreate(&self, old_self: &Self) -> bool {
+		// the rest of the style can only be set on creation
+		self.color != old_self.color
+			|| self.font != old_self.font
+			|| self.align_x != old_self.align_x
+			|| self.align_y != old_self.align_y
+			|| self.bounds != old_self.bounds
+	}
 	fn spatial_aspect<'a>(&self, inner: &Self::Inner) -> SpatialRef {
 		inner.clone().as_spatial().as_spatial_ref()
 	}
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		// the server doesn't tell us the real size, so assume glyphs are about as wide as they are tall
+		let (width, height) = match &self.bounds {
+			Some(bounds) => (bounds.bounds.x, bounds.bounds.y),
+			None => {
+				let lines = self.text.lines();
+				let longest_line = lines.clone().map(|line| line.chars().count()).max();
+				(
+					longest_line.unwrap_or_default() as f32 * self.character_height,
+					lines.count().max(1) as f32 * self.character_height,
+				)
+			}
+		};
+		let x = match self.align_x {
+			XAlign::Left => width / 2.0,
+			XAlign::Center => 0.0,
+			XAlign::Right => -width / 2.0,
+		};
+		let y = match self.align_y {
+			YAlign::Top => -height / 2.0,
+			YAlign::Center => 0.0,
+			YAlign::Bottom => height / 2.0,
+		};
+		Some(BoundingBox {
+			center: [x, y, 0.0].into(),
+			size: [width, height, 0.0].into(),
+		})
+	}
 }
 impl Transformable for Text {
 	fn transform(&self) -> &Transform {
@@ -0,0 +1,185 @@
+use crate::{
+	Context, Element, ValidState,
+	blueprint::BlueprintNode,
+	custom::FnWrapper,
+	element::{ElementDiffer, ParentSpace},
+	inner::ElementInnerMap,
+	resource::ResourceRegistry,
+};
+use stardust_xr_fusion::root::FrameInfo;
+use std::{
+	hash::{DefaultHasher, Hash, Hasher},
+	path::Path,
+	sync::OnceLock,
+	time::Duration,
+};
+
+/// Calls `on_elapsed` once after `duration`.
+pub type Timeout<State> = Timer<State, false>;
+/// Calls `on_elapsed` every `duration`.
+pub type Interval<State> = Timer<State, true>;
+
+#[derive(Default)]
+struct TimerInner {
+	elapsed: f32,
+	fired: bool,
+}
+
+/// Counts time using `FrameInfo::delta`, so it only advances while frames happen.
+/// Resets when the duration or key changes and stops when removed from the tree.
+pub struct Timer<State: ValidState, const REPEAT: bool> {
+	duration: Duration,
+	key: u64,
+	on_elapsed: FnWrapper<dyn Fn(&mut State) + Send + Sync>,
+	inner_key: OnceLock<u64>,
+}
+impl<State: ValidState, const REPEAT: bool> Timer<State, REPEAT> {
+	pub fn new(
+		duration: Duration,
+		on_elapsed: impl Fn(&mut State) + Send + Sync + 'static,
+	) -> Self {
+		Timer {
+			duration,
+			key: 0,
+			on_elapsed: FnWrapper(Box::new(on_elapsed)),
+			inner_key: OnceLock::new(),
+		}
+	}
+	/// When this changes the timer starts over.
+	pub fn key(mut self, key: impl Hash) -> Self {
+		let mut hasher = DefaultHasher::new();
+		key.hash(&mut hasher);
+		self.key = hasher.finish();
+		self
+	}
+}
+
+impl<State: ValidState, const REPEAT: bool> ElementDiffer<State> for Timer<State, REPEAT> {
+	fn create_inner_recursive(
+		&self,
+		inner_key: u64,
+		_context: &Context,
+		_parent_space: &ParentSpace,
+		_element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		_resources: &mut ResourceRegistry,
+	) {
+		let _ = self.inner_key.set(inner_key);
+		inner_map.insert_raw(inner_key, TimerInner::default());
+	}
+
+	fn frame_recursive(
+		&self,
+		_context: &Context,
+		info: &FrameInfo,
+		state: &mut State,
+		inner_map: &mut ElementInnerMap,
+	) {
+		let Some(&inner_key) = self.inner_key.get() else {
+			return;
+		};
+		let Some(inner) = inner_map.get_raw_mut::<TimerInner>(inner_key) else {
+			return;
+		};
+		if inner.fired && !REPEAT {
+			return;
+		}
+
+		inner.elapsed += info.delta;
+		let duration = self.duration.as_secs_f32();
+		if inner.elapsed < duration {
+			return;
+		}
+		inner.fired = true;
+		if REPEAT && duration > 0.0 {
+			// don't try to catch up on every missed interval after a long frame
+			inner.elapsed %= duration;
+		}
+		(self.on_elapsed.0)(state);
+	}
+
+	fn diff_same_type(
+		&self,
+		inner_key: u64,
+		old: &Self,
+		_context: &Context,
+		_parent_space: &ParentSpace,
+		_element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		_resources: &mut ResourceRegistry,
+	) {
+		let _ = self.inner_key.set(inner_key);
+		if self.duration != old.duration || self.key != old.key {
+			inner_map.insert_raw(inner_key, TimerInner::default());
+		}
+	}
+
+	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
+		if let Some(&inner_key) = self.inner_key.get() {
+			inner_map.remove(inner_key);
+		}
+	}
+
+	fn blueprint(&self, _context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		let element_type = if REPEAT { "Interval" } else { "Timeout" };
+		nodes.push(BlueprintNode {
+			element_type: element_type.to_string(),
+			debug: format!("{element_type} {{ duration: {:?} }}", self.duration),
+			children: Vec::new(),
+		});
+	}
+}
+
+impl<State: ValidState, const REPEAT: bool> Element<State> for Timer<State, REPEAT> {}
+
+#[tokio::test]
+async fn asteroids_timer_elements() {
+	use crate::{Projector, Reify, custom::CustomElement, elements::Spatial};
+
+	#[derive(Default)]
+	struct TestState {
+		attempt: u32,
+		timeouts: u32,
+		intervals: u32,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Spatial::default()
+				.build()
+				.child(
+					Timeout::new(Duration::from_secs(1), |state: &mut Self| {
+						state.timeouts += 1
+					})
+					.key(self.attempt),
+				)
+				.child(Interval::new(Duration::from_secs(1), |state: &mut Self| {
+					state.intervals += 1
+				}))
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let info = FrameInfo {
+		delta: 0.5,
+		elapsed: 0.0,
+	};
+	let mut state = TestState::default();
+	let mut projector = Projector::headless(&state, &context, "/".into());
+
+	for _ in 0..4 {
+		projector.frame(&context, &info, &mut state);
+		projector.update(&context, &mut state);
+	}
+	assert_eq!(state.timeouts, 1);
+	assert_eq!(state.intervals, 2);
+
+	// a new key restarts the timeout
+	state.attempt += 1;
+	projector.update(&context, &mut state);
+	for _ in 0..2 {
+		projector.frame(&context, &info, &mut state);
+		projector.update(&context, &mut state);
+	}
+	assert_eq!(state.timeouts, 2);
+	assert_eq!(state.intervals, 3);
+}
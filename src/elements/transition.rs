@@ -0,0 +1,361 @@
+use crate::{
+	Context, Element, ValidState,
+	blueprint::BlueprintNode,
+	element::{ElementDiffer, Leaving, ParentSpace, gen_inner_key},
+	elements::Easing,
+	inner::ElementInnerMap,
+	resource::ResourceRegistry,
+};
+use stardust_xr_fusion::root::FrameInfo;
+use std::{
+	marker::PhantomData,
+	path::{Path, PathBuf},
+	sync::{Arc, OnceLock},
+	time::Duration,
+};
+
+struct TransitionInner {
+	/// 0 is fully hidden, 1 is fully shown
+	progress: f32,
+	parent_space: ParentSpace,
+	element_path: PathBuf,
+}
+
+/// Plays an enter transition when created and keeps its subtree alive for an exit transition after it's removed.
+///
+/// The builder gets the eased visibility, from 0 (gone) to 1 (fully there),
+/// so e.g. `|t| Spatial::default().scl([t; 3]).build()` scales in and out.
+pub struct Transition<State: ValidState, E: Element<State>, F: Fn(f32) -> E + Send + Sync + 'static>
+{
+	builder: Arc<F>,
+	enter: Duration,
+	exit: Duration,
+	easing: Easing,
+	subtree: OnceLock<Arc<E>>,
+	inner_key: OnceLock<u64>,
+	phantom: PhantomData<State>,
+}
+impl<State: ValidState, E: Element<State>, F: Fn(f32) -> E + Send + Sync + 'static>
+	Transition<State, E, F>
+{
+	pub fn new(builder: F) -> Self {
+		Transition {
+			builder: Arc::new(builder),
+			enter: Duration::from_millis(250),
+			exit: Duration::from_millis(250),
+			easing: Easing::default(),
+			subtree: OnceLock::new(),
+			inner_key: OnceLock::new(),
+			phantom: PhantomData,
+		}
+	}
+	pub fn enter(mut self, enter: Duration) -> Self {
+		self.enter = enter;
+		self
+	}
+	pub fn exit(mut self, exit: Duration) -> Self {
+		self.exit = exit;
+		self
+	}
+	pub fn easing(mut self, easing: Easing) -> Self {
+		self.easing = easing;
+		self
+	}
+
+	fn subtree_key(inner_key: u64) -> u64 {
+		gen_inner_key::<E>(inner_key, 0)
+	}
+	fn build(&self, progress: f32) -> &E {
+		self.subtree
+			.get_or_init(|| Arc::new((self.builder)(self.easing.apply(progress))))
+	}
+}
+
+impl<State: ValidState, E: Element<State>, F: Fn(f32) -> E + Send + Sync + 'static>
+	ElementDiffer<State> for Transition<State, E, F>
+{
+	fn create_inner_recursive(
+		&self,
+		inner_key: u64,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let _ = self.inner_key.set(inner_key);
+		let subtree_key = Self::subtree_key(inner_key);
+
+		// came back while still leaving, so turn around from where it is
+		let leaving = inner_map.take_leaving(subtree_key).and_then(|leaving| {
+			if leaving.as_any().is::<LeavingSubtree<State, E, F>>() {
+				leaving.into_any().downcast().ok()
+			} else {
+				// something else was leaving at the same key, it has to make room now
+				let instant_destroy = inner_map.set_instant_destroy(true);
+				leaving.destroy(inner_map);
+				inner_map.set_instant_destroy(instant_destroy);
+				None
+			}
+		});
+		let progress = match &leaving {
+			Some(leaving) => leaving.progress,
+			None if self.enter.is_zero() => 1.0,
+			None => 0.0,
+		};
+		inner_map.insert_raw(
+			inner_key,
+			TransitionInner {
+				progress,
+				parent_space: parent_space.clone(),
+				element_path: element_path.to_path_buf(),
+			},
+		);
+
+		let subtree = self.build(progress);
+		match leaving {
+			Some(leaving) => subtree.diff_same_type(
+				subtree_key,
+				&leaving.subtree,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			),
+			None => subtree.create_inner_recursive(
+				subtree_key,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			),
+		}
+	}
+
+	fn frame_recursive(
+		&self,
+		context: &Context,
+		info: &FrameInfo,
+		state: &mut State,
+		inner_map: &mut ElementInnerMap,
+	) {
+		let enter = self.enter.as_secs_f32();
+		let entering = self
+			.inner_key
+			.get()
+			.and_then(|&inner_key| inner_map.get_raw_mut::<TransitionInner>(inner_key))
+			.filter(|inner| inner.progress < 1.0)
+			.map(|inner| {
+				inner.progress = (inner.progress + info.delta / enter).min(1.0);
+			})
+			.is_some();
+		if entering {
+			inner_map.request_update();
+		}
+
+		if let Some(subtree) = self.subtree.get() {
+			subtree.frame_recursive(context, info, state, inner_map);
+		}
+	}
+
+	fn diff_same_type(
+		&self,
+		inner_key: u64,
+		old: &Self,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let _ = self.inner_key.set(inner_key);
+		let (Some(inner), Some(old_subtree)) = (
+			inner_map.get_raw_mut::<TransitionInner>(inner_key),
+			old.subtree.get(),
+		) else {
+			self.create_inner_recursive(
+				inner_key,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			);
+			return;
+		};
+		inner.parent_space = parent_space.clone();
+		inner.element_path = element_path.to_path_buf();
+		let progress = inner.progress;
+
+		self.build(progress).diff_same_type(
+			Self::subtree_key(inner_key),
+			old_subtree,
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+	}
+
+	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
+		let Some(&inner_key) = self.inner_key.get() else {
+			return;
+		};
+		let inner = inner_map
+			.get_raw_mut::<TransitionInner>(inner_key)
+			.map(|inner| {
+				(
+					inner.progress,
+					inner.parent_space.clone(),
+					std::mem::take(&mut inner.element_path),
+				)
+			});
+		inner_map.remove(inner_key);
+		let Some(subtree) = self.subtree.get() else {
+			return;
+		};
+
+		match inner {
+			Some((progress, parent_space, element_path))
+				if !inner_map.instant_destroy() && !self.exit.is_zero() && progress > 0.0 =>
+			{
+				inner_map.push_leaving(Box::new(LeavingSubtree {
+					inner_key: Self::subtree_key(inner_key),
+					parent_space,
+					element_path,
+					subtree: subtree.clone(),
+					builder: self.builder.clone(),
+					progress,
+					exit: self.exit.as_secs_f32(),
+					easing: self.easing,
+				}));
+			}
+			_ => subtree.destroy_inner_recursive(inner_map),
+		}
+	}
+
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		(self.builder)(1.0).blueprint(context, nodes)
+	}
+}
+
+impl<State: ValidState, E: Element<State>, F: Fn(f32) -> E + Send + Sync + 'static> Element<State>
+	for Transition<State, E, F>
+{
+}
+
+struct LeavingSubtree<State: ValidState, E: Element<State>, F: Fn(f32) -> E + Send + Sync + 'static>
+{
+	inner_key: u64,
+	parent_space: ParentSpace,
+	element_path: PathBuf,
+	subtree: Arc<E>,
+	builder: Arc<F>,
+	progress: f32,
+	exit: f32,
+	easing: Easing,
+}
+impl<State: ValidState, E: Element<State>, F: Fn(f32) -> E + Send + Sync + 'static> Leaving
+	for LeavingSubtree<State, E, F>
+{
+	fn inner_key(&self) -> u64 {
+		self.inner_key
+	}
+	fn tick(&mut self, delta: f32) {
+		self.progress = (self.progress - delta / self.exit).max(0.0);
+	}
+	fn finished(&self) -> bool {
+		self.progress <= 0.0
+	}
+	fn rebuild(
+		&mut self,
+		context: &Context,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let subtree = (self.builder)(self.easing.apply(self.progress));
+		subtree.diff_same_type(
+			self.inner_key,
+			&self.subtree,
+			context,
+			&self.parent_space,
+			&self.element_path,
+			inner_map,
+			resources,
+		);
+		self.subtree = Arc::new(subtree);
+	}
+	fn destroy(&self, inner_map: &mut ElementInnerMap) {
+		self.subtree.destroy_inner_recursive(inner_map);
+	}
+	fn as_any(&self) -> &dyn std::any::Any {
+		self
+	}
+	fn into_any(self: Box<Self>) -> Box<dyn std::any::Any> {
+		self
+	}
+}
+
+#[tokio::test]
+async fn asteroids_transition_element() {
+	use crate::{Projector, Reify, Transformable, custom::CustomElement, elements::Spatial};
+
+	struct TestState {
+		shown: bool,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			self.shown.then(|| {
+				Transition::new(|t| Spatial::default().scl([t; 3]).build())
+					.enter(Duration::from_secs(1))
+					.exit(Duration::from_secs(1))
+					.easing(Easing::Linear)
+			})
+		}
+	}
+	fn scale(projector: &Projector<TestState>) -> Option<f32> {
+		let spatial = projector.scene().unwrap().find("Spatial").next()?;
+		Some(spatial.transform.unwrap().scale.unwrap().x)
+	}
+
+	let context = Context::headless().await.unwrap();
+	let info = FrameInfo {
+		delta: 0.5,
+		elapsed: 0.0,
+	};
+	let mut state = TestState { shown: true };
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	assert_eq!(scale(&projector), Some(0.0));
+	projector.frame(&context, &info, &mut state);
+	projector.update(&context, &mut state);
+	assert_eq!(scale(&projector), Some(0.5));
+
+	// removed halfway through entering, so it's still around while it leaves
+	state.shown = false;
+	projector.update(&context, &mut state);
+	assert_eq!(scale(&projector), Some(0.5));
+	projector.frame(&context, &info, &mut state);
+	projector.update(&context, &mut state);
+	assert_eq!(scale(&projector), None);
+
+	state.shown = true;
+	projector.update(&context, &mut state);
+	for _ in 0..2 {
+		projector.frame(&context, &info, &mut state);
+		projector.update(&context, &mut state);
+	}
+	assert_eq!(scale(&projector), Some(1.0));
+	state.shown = false;
+	projector.update(&context, &mut state);
+	projector.frame(&context, &info, &mut state);
+	projector.update(&context, &mut state);
+	assert_eq!(scale(&projector), Some(0.5));
+	// brought back while leaving, so it turns around instead of popping in again
+	state.shown = true;
+	projector.update(&context, &mut state);
+	assert_eq!(scale(&projector), Some(0.5));
+	assert_eq!(projector.scene().unwrap().len(), 1);
+}
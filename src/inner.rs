@@ -1,27 +1,176 @@
-use crate::{ValidState, custom::CustomElement};
+use crate::{
+	ValidState,
+	custom::CustomElement,
+	element::{ElementError, Leaving},
+	resource::ResourceKey,
+	scene::Scene,
+};
 use rustc_hash::FxHashMap;
 use std::any::Any;
 
+/// A callback that needs `&mut State` but was queued while diffing, run at the start of the next frame
+pub struct StateHook(Box<dyn FnOnce(&mut dyn Any) + Send + Sync>);
+impl StateHook {
+	pub fn new<State: ValidState>(hook: impl FnOnce(&mut State) + Send + Sync + 'static) -> Self {
+		StateHook(Box::new(move |state: &mut dyn Any| {
+			if let Some(state) = state.downcast_mut::<State>() {
+				hook(state);
+			}
+		}))
+	}
+	pub fn call(self, state: &mut dyn Any) {
+		(self.0)(state)
+	}
+}
+impl std::fmt::Debug for StateHook {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("StateHook").finish()
+	}
+}
+
 #[derive(Debug, Default)]
-pub struct ElementInnerMap(FxHashMap<u64, Box<dyn Any + Send + Sync>>);
+pub struct ElementInnerMap {
+	inners: FxHashMap<u64, Box<dyn Any + Send + Sync>>,
+	scene: Option<Scene>,
+	errors: Vec<ElementError>,
+	hooks: Vec<StateHook>,
+	update_requested: bool,
+	leaving: Vec<Box<dyn Leaving>>,
+	instant_destroy: bool,
+	resource_keys: FxHashMap<u64, ResourceKey>,
+	released_resources: Vec<ResourceKey>,
+}
 impl ElementInnerMap {
+	/// An inner map that also records every element into a [`Scene`], for headless and inspected projectors
+	pub fn recording() -> Self {
+		ElementInnerMap {
+			scene: Some(Scene::default()),
+			..Default::default()
+		}
+	}
 	pub fn insert<State: ValidState, E: CustomElement<State>>(
 		&mut self,
 		key: u64,
 		inner: E::Inner,
 	) {
-		self.0.insert(key, Box::new(inner));
+		self.inners.insert(key, Box::new(inner));
+	}
+	/// For elements that keep their own state instead of going through `CustomElement`
+	pub fn insert_raw<T: Send + Sync + 'static>(&mut self, key: u64, value: T) {
+		self.inners.insert(key, Box::new(value));
+	}
+	pub fn get_raw<T: 'static>(&self, key: u64) -> Option<&T> {
+		self.inners.get(&key)?.downcast_ref()
+	}
+	pub fn get_raw_mut<T: 'static>(&mut self, key: u64) -> Option<&mut T> {
+		self.inners.get_mut(&key)?.downcast_mut()
 	}
 	pub fn get<State: ValidState, E: CustomElement<State>>(&self, key: u64) -> Option<&E::Inner> {
-		self.0.get(&key)?.downcast_ref()
+		self.inners.get(&key)?.downcast_ref()
 	}
 	pub fn get_mut<State: ValidState, E: CustomElement<State>>(
 		&mut self,
 		key: u64,
 	) -> Option<&mut E::Inner> {
-		self.0.get_mut(&key)?.downcast_mut()
+		self.inners.get_mut(&key)?.downcast_mut()
+	}
+	/// If anything was created at `key`, either an inner or a recorded scene node
+	pub fn contains(&self, key: u64) -> bool {
+		self.inners.contains_key(&key)
+			|| self.scene.as_ref().is_some_and(|scene| scene.contains(key))
 	}
 	pub fn remove(&mut self, key: u64) {
-		self.0.remove(&key);
+		self.inners.remove(&key);
+		if let Some(resource_key) = self.resource_keys.remove(&key) {
+			self.released_resources.push(resource_key);
+		}
+		if let Some(scene) = &mut self.scene {
+			scene.remove(key);
+		}
+	}
+	/// Which resource the element at `key` uses, released when it gets removed
+	pub fn set_resource_key(&mut self, key: u64, resource_key: ResourceKey) {
+		self.resource_keys.insert(key, resource_key);
+	}
+	pub fn resource_key(&self, key: u64) -> Option<ResourceKey> {
+		self.resource_keys.get(&key).copied()
+	}
+	/// Resources of elements removed since the last call, for the `ResourceRegistry` to release
+	pub fn take_released_resources(&mut self) -> Vec<ResourceKey> {
+		std::mem::take(&mut self.released_resources)
+	}
+
+	pub fn scene(&self) -> Option<&Scene> {
+		self.scene.as_ref()
+	}
+	pub fn scene_mut(&mut self) -> Option<&mut Scene> {
+		self.scene.as_mut()
+	}
+
+	/// Errors from elements that failed to create since the last time they were cleared
+	pub fn push_error(&mut self, error: ElementError) {
+		self.errors.push(error);
+	}
+	pub fn error_count(&self) -> usize {
+		self.errors.len()
+	}
+	pub fn take_errors_since(&mut self, start: usize) -> Vec<ElementError> {
+		self.errors.split_off(start.min(self.errors.len()))
+	}
+	pub fn clear_errors(&mut self) {
+		self.errors.clear();
+	}
+
+	pub fn queue_hook(&mut self, hook: StateHook) {
+		self.hooks.push(hook);
+	}
+	pub fn hook_count(&self) -> usize {
+		self.hooks.len()
+	}
+	pub fn take_hooks_since(&mut self, start: usize) -> Vec<StateHook> {
+		self.hooks.split_off(start.min(self.hooks.len()))
+	}
+
+	/// Ask for another `Projector::update` even if the state didn't change, e.g. while animating
+	pub fn request_update(&mut self) {
+		self.update_requested = true;
+	}
+	pub fn update_requested(&self) -> bool {
+		self.update_requested
+	}
+	pub fn clear_update_request(&mut self) {
+		self.update_requested = false;
+	}
+
+	/// Keep a subtree that was removed from the blueprint alive until it's done leaving
+	pub fn push_leaving(&mut self, leaving: Box<dyn Leaving>) {
+		self.leaving.push(leaving);
+	}
+	/// Take back a subtree that's still leaving, when an element gets created at the same key again
+	pub fn take_leaving(&mut self, key: u64) -> Option<Box<dyn Leaving>> {
+		let index = self
+			.leaving
+			.iter()
+			.position(|leaving| leaving.inner_key() == key)?;
+		Some(self.leaving.remove(index))
+	}
+	pub fn take_all_leaving(&mut self) -> Vec<Box<dyn Leaving>> {
+		std::mem::take(&mut self.leaving)
+	}
+	pub fn tick_leaving(&mut self, delta: f32) {
+		for leaving in &mut self.leaving {
+			leaving.tick(delta);
+		}
+		if !self.leaving.is_empty() {
+			self.request_update();
+		}
+	}
+	/// While set, elements get destroyed right away instead of leaving, e.g. when their parent is already gone
+	pub fn instant_destroy(&self) -> bool {
+		self.instant_destroy
+	}
+	/// Returns the previous value so it can be restored
+	pub fn set_instant_destroy(&mut self, instant_destroy: bool) -> bool {
+		std::mem::replace(&mut self.instant_destroy, instant_destroy)
 	}
 }
@@ -0,0 +1,140 @@
+//! Publishes the live element tree on the session bus when `ASTEROIDS_INSPECT` is set,
+//! so you can see what a client actually created without adding `tracing` calls everywhere.
+//!
+//! Every inspected client owns a bus name starting with [`BUS_NAME_PREFIX`] followed by its app ID,
+//! use [`InspectorProxy`] or `cargo run --example inspect` to dump it.
+
+use crate::scene::Scene;
+use serde::{Deserialize, Serialize};
+use stardust_xr_fusion::spatial::Transform;
+use std::sync::{Arc, Mutex};
+use zbus::{Connection, zvariant::Type};
+
+pub const BUS_NAME_PREFIX: &str = "org.stardustxr.asteroids.Inspector.";
+pub const OBJECT_PATH: &str = "/org/stardustxr/asteroids/Inspector";
+
+/// If inspection was asked for with the `ASTEROIDS_INSPECT` environment variable.
+pub fn enabled() -> bool {
+	std::env::var_os("ASTEROIDS_INSPECT").is_some()
+}
+
+/// One element of the tree, as sent over D-Bus.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct InspectedNode {
+	/// Element name without the namespace or generics, e.g. `Button`
+	pub element_type: String,
+	/// Unique path of this element, e.g. `/Spatial_1234/Button_5678`
+	pub path: String,
+	/// Path of the element this one is parented under, or the projector's root path
+	pub parent: String,
+	/// Inner key the element is stored under
+	pub key: u64,
+	/// Local translation, zero if the element doesn't have a transform
+	pub translation: (f32, f32, f32),
+	/// Local rotation as a quaternion (x, y, z, w), identity if the element doesn't have a transform
+	pub rotation: (f32, f32, f32, f32),
+	/// Local scale, one if the element doesn't have a transform
+	pub scale: (f32, f32, f32),
+	/// `Debug` output of the element declaration
+	pub debug: String,
+}
+
+#[zbus::proxy(
+	interface = "org.stardustxr.asteroids.Inspector",
+	default_path = "/org/stardustxr/asteroids/Inspector"
+)]
+pub trait Inspector {
+	/// Every element currently alive in the client, in no particular order
+	fn tree(&self) -> zbus::Result<Vec<InspectedNode>>;
+}
+
+struct InspectorInterface(Arc<Mutex<Vec<InspectedNode>>>);
+#[zbus::interface(name = "org.stardustxr.asteroids.Inspector")]
+impl InspectorInterface {
+	fn tree(&self) -> Vec<InspectedNode> {
+		self.0.lock().unwrap().clone()
+	}
+}
+
+/// Handle to the published tree, refreshed after every update.
+pub(crate) struct InspectorHandle {
+	nodes: Arc<Mutex<Vec<InspectedNode>>>,
+}
+impl InspectorHandle {
+	pub(crate) async fn serve(connection: &Connection, app_id: &str) -> zbus::Result<Self> {
+		let nodes = Arc::new(Mutex::new(Vec::new()));
+		connection
+			.object_server()
+			.at(OBJECT_PATH, InspectorInterface(nodes.clone()))
+			.await?;
+		connection
+			.request_name(format!("{BUS_NAME_PREFIX}{app_id}"))
+			.await?;
+		Ok(InspectorHandle { nodes })
+	}
+
+	/// Replace the published tree with every node of `scenes`, one per projector
+	pub(crate) fn publish<'a>(&self, scenes: impl IntoIterator<Item = &'a Scene>) {
+		let nodes = scenes
+			.into_iter()
+			.flat_map(Scene::nodes)
+			.map(|node| {
+				let transform = node.transform.unwrap_or(Transform::none());
+				let translation = transform.translation.unwrap_or([0.0; 3].into());
+				let rotation = transform.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0].into());
+				let scale = transform.scale.unwrap_or([1.0; 3].into());
+				InspectedNode {
+					element_type: node.element_type.to_string(),
+					path: node.path.to_string_lossy().into_owned(),
+					parent: node.parent.to_string_lossy().into_owned(),
+					key: node.key,
+					translation: (translation.x, translation.y, translation.z),
+					rotation: (rotation.v.x, rotation.v.y, rotation.v.z, rotation.s),
+					scale: (scale.x, scale.y, scale.z),
+					debug: node.debug.clone(),
+				}
+			})
+			.collect();
+		*self.nodes.lock().unwrap() = nodes;
+	}
+}
+
+#[tokio::test]
+async fn asteroids_inspector() {
+	use crate::{
+		Context, Element, Projector, Reify, Transformable,
+		custom::CustomElement,
+		elements::{Spatial, Text},
+	};
+
+	struct TestState;
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Spatial::default()
+				.pos([0.0, 1.0, 0.0])
+				.build()
+				.child(Text::new("inspect me").build())
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let projector = Projector::headless(&TestState, &context, "/".into());
+	let inspector = InspectorHandle {
+		nodes: Arc::default(),
+	};
+	inspector.publish(projector.scene());
+
+	let nodes = inspector.nodes.lock().unwrap();
+	let spatial = nodes
+		.iter()
+		.find(|node| node.element_type == "Spatial")
+		.unwrap();
+	let text = nodes
+		.iter()
+		.find(|node| node.element_type == "Text")
+		.unwrap();
+	assert_eq!(spatial.translation, (0.0, 1.0, 0.0));
+	assert_eq!(text.parent, spatial.path);
+	assert!(text.debug.contains("inspect me"));
+	assert_ne!(text.key, spatial.key);
+}
@@ -1,27 +1,41 @@
+mod blueprint;
 pub mod client;
 mod context;
 mod custom;
 mod dynamic_element;
 mod element;
+mod element_ref;
 pub mod elements;
 mod inner;
+pub mod inspector;
 mod mapped;
+mod memo;
+mod message;
+mod provider;
+pub mod recording;
 mod resource;
+mod scene;
 mod util;
 
 use bumpalo::{Bump, boxed::Box};
-use element::ElementDiffer;
+use element::{ElementDiffer, ParentSpace, update_leaving};
 use inner::ElementInnerMap;
 use mapped::Mapped;
 use resource::ResourceRegistry;
 use stardust_xr_fusion::{root::FrameInfo, spatial::SpatialRef};
 use std::path::PathBuf;
 
+pub use blueprint::{Blueprint, BlueprintNode};
 pub use client::ClientState;
 pub use context::*;
 pub use custom::*;
 pub use dynamic_element::*;
-pub use element::{Element, gen_inner_key};
+pub use element::{Element, ElementError, KeyedChildren, gen_inner_key};
+pub use element_ref::ElementRef;
+pub use memo::Memo;
+pub use message::Update;
+pub use provider::{Consumer, Provider};
+pub use scene::*;
 pub use util::*;
 
 pub trait ValidState: Sized + Send + Sync + 'static {}
@@ -41,7 +55,13 @@ pub trait Reify: ValidState + Sized + Send + Sync + 'static {
 	}
 }
 
-pub struct Projector<State: Reify>(Option<ProjectorInner<State>>);
+/// Builds the blueprint of a projector that doesn't use [`Reify::reify`], e.g. a [`client::View`]
+type ReifyFn<State> = std::boxed::Box<dyn Fn(&State) -> DynamicElement<State> + Send + Sync>;
+
+pub struct Projector<State: Reify> {
+	inner: Option<ProjectorInner<State>>,
+	reify: Option<ReifyFn<State>>,
+}
 impl<State: Reify> Projector<State> {
 	pub fn create(
 		state: &State,
@@ -49,22 +69,116 @@ impl<State: Reify> Projector<State> {
 		parent_spatial: SpatialRef,
 		root_element_path: PathBuf,
 	) -> Projector<State> {
-		let mut inner_map = ElementInnerMap::default();
+		Self::create_in(
+			state,
+			context,
+			parent_spatial,
+			Self::live_inner_map(),
+			root_element_path,
+			None,
+		)
+	}
+	/// Like [`Projector::create`] but reifying with `reify` instead of `State::reify`,
+	/// so several projectors can show different parts of the same state.
+	pub fn create_with<E: Element<State>>(
+		state: &State,
+		context: &Context,
+		parent_spatial: SpatialRef,
+		root_element_path: PathBuf,
+		reify: impl Fn(&State) -> E + Send + Sync + 'static,
+	) -> Projector<State> {
+		Self::create_in(
+			state,
+			context,
+			parent_spatial,
+			Self::live_inner_map(),
+			root_element_path,
+			Some(std::boxed::Box::new(move |state| reify(state).dynamic())),
+		)
+	}
+	/// Create a projector under the fake root of a [`Context::headless`], recording the element tree into a [`Scene`].
+	/// Elements get created, diffed and framed like they would be on a server, so their callbacks run too.
+	#[cfg(any(test, feature = "headless"))]
+	pub fn headless(
+		state: &State,
+		context: &Context,
+		root_element_path: PathBuf,
+	) -> Projector<State> {
+		Self::create_in(
+			state,
+			context,
+			context.headless_root(),
+			ElementInnerMap::recording(),
+			root_element_path,
+			None,
+		)
+	}
+	/// A headless projector reifying with `reify`, see [`Projector::create_with`].
+	#[cfg(any(test, feature = "headless"))]
+	pub fn headless_with<E: Element<State>>(
+		state: &State,
+		context: &Context,
+		root_element_path: PathBuf,
+		reify: impl Fn(&State) -> E + Send + Sync + 'static,
+	) -> Projector<State> {
+		Self::create_in(
+			state,
+			context,
+			context.headless_root(),
+			ElementInnerMap::recording(),
+			root_element_path,
+			Some(std::boxed::Box::new(move |state| reify(state).dynamic())),
+		)
+	}
+	fn live_inner_map() -> ElementInnerMap {
+		if inspector::enabled() {
+			ElementInnerMap::recording()
+		} else {
+			ElementInnerMap::default()
+		}
+	}
+	fn create_in(
+		state: &State,
+		context: &Context,
+		root: ParentSpace,
+		inner_map: ElementInnerMap,
+		root_element_path: PathBuf,
+		reify: Option<ReifyFn<State>>,
+	) -> Projector<State> {
+		let inner = match &reify {
+			Some(reify) => {
+				Self::create_inner(reify(state), context, root, inner_map, root_element_path)
+			}
+			None => Self::create_inner(state.reify(), context, root, inner_map, root_element_path),
+		};
+		Projector {
+			inner: Some(inner),
+			reify,
+		}
+	}
+	fn create_inner<E: Element<State>>(
+		blueprint: E,
+		context: &Context,
+		root: ParentSpace,
+		mut inner_map: ElementInnerMap,
+		root_element_path: PathBuf,
+	) -> ProjectorInner<State> {
 		let mut resource_registry = ResourceRegistry::default();
 
-		let blueprint = state.reify();
 		blueprint.create_inner_recursive(
 			0,
 			context,
-			&parent_spatial,
+			&root,
 			&root_element_path,
 			&mut inner_map,
 			&mut resource_registry,
 		);
+		// anything not caught by an `ErrorBoundary` has already been logged
+		inner_map.clear_errors();
 		let bump = Bump::new();
 
-		Self(Some(ProjectorInner::new(
-			parent_spatial,
+		ProjectorInner::new(
+			root,
 			inner_map,
 			resource_registry,
 			root_element_path,
@@ -74,17 +188,50 @@ impl<State: Reify> Projector<State> {
 				let raw = Box::into_raw(concrete);
 				Box::from_raw(raw) // coerce the type manually since bumpalo can't implement `CoerceUnsized`
 			},
-		)))
+		)
+	}
+
+	/// The recorded element tree, only present on headless projectors and when `ASTEROIDS_INSPECT` is set.
+	pub fn scene(&self) -> Option<&Scene> {
+		self.inner.as_ref()?.borrow_inner_map().scene()
+	}
+	/// If an element asked for an update on the last frame even if the state didn't change, e.g. to animate.
+	pub fn update_requested(&self) -> bool {
+		self.inner
+			.as_ref()
+			.is_some_and(|projector| projector.borrow_inner_map().update_requested())
+	}
+	/// The typed inner of the element `element_ref` is attached to, if it's an `E`.
+	pub fn inner<S: ValidState, E: CustomElement<S>>(
+		&self,
+		element_ref: &ElementRef,
+	) -> Option<&E::Inner> {
+		let inner_key = element_ref.inner_key()?;
+		self.inner
+			.as_ref()?
+			.borrow_inner_map()
+			.get::<S, E>(inner_key)
 	}
 
 	#[tracing::instrument(level = "debug", skip_all)]
 	pub fn update(&mut self, context: &Context, state: &mut State) {
-		let Some(mut projector) = self.0.take() else {
+		let Some(projector) = self.inner.take() else {
 			tracing::warn!("Projector not found on update... how??");
 			return;
 		};
-		let blueprint = state.reify();
+		let projector = match &self.reify {
+			Some(reify) => Self::diff(projector, context, reify(state)),
+			None => Self::diff(projector, context, state.reify()),
+		};
+		self.inner.replace(projector);
+	}
+	fn diff<E: Element<State>>(
+		mut projector: ProjectorInner<State>,
+		context: &Context,
+		blueprint: E,
+	) -> ProjectorInner<State> {
 		projector.with_mut(|fields| {
+			fields.inner_map.clear_update_request();
 			blueprint.dynamic_diff(
 				0,
 				fields.old.as_ref(),
@@ -94,6 +241,11 @@ impl<State: Reify> Projector<State> {
 				fields.inner_map,
 				&mut *fields.resource_registry,
 			);
+			update_leaving(context, fields.inner_map, fields.resource_registry);
+			fields
+				.resource_registry
+				.release(fields.inner_map.take_released_resources());
+			fields.inner_map.clear_errors();
 		});
 
 		// Move out fields by destructuring
@@ -106,7 +258,7 @@ impl<State: Reify> Projector<State> {
 			..
 		} = projector.into_heads();
 		bump.reset();
-		self.0.replace(ProjectorInner::new(
+		ProjectorInner::new(
 			root,
 			inner_map,
 			resource_registry,
@@ -118,14 +270,19 @@ impl<State: Reify> Projector<State> {
 				// coerce the type manually since bumpalo can't implement `CoerceUnsized`
 				Box::from_raw(old_raw)
 			},
-		));
+		)
 	}
 	pub fn frame(&mut self, context: &Context, info: &FrameInfo, state: &mut State) {
-		let Some(projector) = self.0.as_mut() else {
+		let Some(projector) = self.inner.as_mut() else {
 			tracing::warn!("Projector not found on frame... how??");
 			return;
 		};
 		projector.with_mut(|fields| {
+			// lifecycle hooks queued since the last frame
+			for hook in fields.inner_map.take_hooks_since(0) {
+				hook.call(state);
+			}
+			fields.inner_map.tick_leaving(info.delta);
 			fields
 				.old
 				.dynamic_frame_recursive(context, info, state, fields.inner_map);
@@ -133,9 +290,28 @@ impl<State: Reify> Projector<State> {
 	}
 }
 
+impl<State: Reify> Drop for Projector<State> {
+	fn drop(&mut self) {
+		let Some(mut projector) = self.inner.take() else {
+			return;
+		};
+		// destroy every inner before the resources they were created with go away
+		projector.with_mut(|fields| {
+			fields.inner_map.set_instant_destroy(true);
+			fields.old.dynamic_destroy_inner_recursive(fields.inner_map);
+			for leaving in fields.inner_map.take_all_leaving() {
+				leaving.destroy(fields.inner_map);
+			}
+			fields
+				.resource_registry
+				.release(fields.inner_map.take_released_resources());
+		});
+	}
+}
+
 #[ouroboros::self_referencing]
 struct ProjectorInner<State: Reify> {
-	root: SpatialRef,
+	root: ParentSpace,
 	inner_map: ElementInnerMap,
 	resource_registry: ResourceRegistry,
 	root_element_path: PathBuf,
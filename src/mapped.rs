@@ -1,8 +1,12 @@
 use crate::{
-	Context, Element, ElementDiffer, ValidState, inner::ElementInnerMap, resource::ResourceRegistry,
+	Context, Element, ElementDiffer, ValidState,
+	blueprint::BlueprintNode,
+	element::ParentSpace,
+	inner::{ElementInnerMap, StateHook},
+	resource::ResourceRegistry,
 };
-use stardust_xr_fusion::{root::FrameInfo, spatial::SpatialRef};
-use std::marker::PhantomData;
+use stardust_xr_fusion::{root::FrameInfo, spatial::BoundingBox};
+use std::{marker::PhantomData, sync::Arc};
 
 pub struct Mapped<
 	State: ValidState,
@@ -11,7 +15,7 @@ pub struct Mapped<
 	E: Element<WrappedState>,
 > {
 	wrapped: E,
-	mapper: Option<F>,
+	mapper: Option<Arc<F>>,
 	phantom: PhantomData<State>,
 }
 
@@ -25,10 +29,25 @@ impl<
 	pub fn new(wrapped: E, mapper: F) -> Self {
 		Self {
 			wrapped,
-			mapper: Some(mapper),
+			mapper: Some(Arc::new(mapper)),
 			phantom: PhantomData,
 		}
 	}
+
+	/// Hooks queued by the wrapped elements take `&mut WrappedState`, so map them to take `&mut State`
+	fn map_hooks_since(&self, start: usize, inner_map: &mut ElementInnerMap) {
+		let Some(mapper) = &self.mapper else {
+			return;
+		};
+		for hook in inner_map.take_hooks_since(start) {
+			let mapper = mapper.clone();
+			inner_map.queue_hook(StateHook::new(move |state: &mut State| {
+				if let Some(mapped_state) = mapper(state) {
+					hook.call(mapped_state);
+				}
+			}));
+		}
+	}
 }
 
 impl<
@@ -42,11 +61,12 @@ impl<
 		&self,
 		inner_key: u64,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &std::path::Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
 	) {
+		let hook_start = inner_map.hook_count();
 		self.wrapped.create_inner_recursive(
 			inner_key,
 			context,
@@ -55,6 +75,7 @@ impl<
 			inner_map,
 			resources,
 		);
+		self.map_hooks_since(hook_start, inner_map);
 	}
 
 	fn frame_recursive(
@@ -77,11 +98,12 @@ impl<
 		inner_key: u64,
 		old: &Self,
 		context: &Context,
-		parent_space: &SpatialRef,
+		parent_space: &ParentSpace,
 		element_path: &std::path::Path,
 		inner_map: &mut ElementInnerMap,
 		resources: &mut ResourceRegistry,
 	) {
+		let hook_start = inner_map.hook_count();
 		self.wrapped.diff_same_type(
 			inner_key,
 			&old.wrapped,
@@ -91,10 +113,21 @@ impl<
 			inner_map,
 			resources,
 		);
+		self.map_hooks_since(hook_start, inner_map);
 	}
 
 	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
+		let hook_start = inner_map.hook_count();
 		self.wrapped.destroy_inner_recursive(inner_map);
+		self.map_hooks_since(hook_start, inner_map);
+	}
+
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		self.wrapped.bounds_hint()
+	}
+
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		self.wrapped.blueprint(context, nodes)
 	}
 }
 
@@ -0,0 +1,263 @@
+use crate::{
+	Context, Element, ValidState,
+	blueprint::BlueprintNode,
+	element::{ElementDiffer, ParentSpace},
+	inner::ElementInnerMap,
+	resource::ResourceRegistry,
+};
+use rustc_hash::FxHashSet;
+use stardust_xr_fusion::{root::FrameInfo, spatial::BoundingBox};
+use std::{
+	any::TypeId,
+	marker::PhantomData,
+	path::Path,
+	sync::{Arc, OnceLock},
+};
+
+/// A subtree that only gets rebuilt and diffed when `key` changes.
+///
+/// The builder is called lazily, so when the key equals the previous one the old subtree is reused as-is
+/// (unless a [`crate::Provider`] above it whose value the subtree reads changed it).
+/// `frame` still runs on the subtree every frame so input keeps working.
+pub struct Memo<
+	State: ValidState,
+	K: PartialEq + Clone + Send + Sync + 'static,
+	E: Element<State>,
+	F: Fn(&K) -> E + Send + Sync + 'static,
+> {
+	key: K,
+	builder: F,
+	subtree: OnceLock<Arc<E>>,
+	/// Provided types the subtree read while being created/diffed
+	consumed: OnceLock<FxHashSet<TypeId>>,
+	phantom: PhantomData<State>,
+}
+impl<
+	State: ValidState,
+	K: PartialEq + Clone + Send + Sync + 'static,
+	E: Element<State>,
+	F: Fn(&K) -> E + Send + Sync + 'static,
+> Memo<State, K, E, F>
+{
+	pub fn new(key: K, builder: F) -> Self {
+		Memo {
+			key,
+			builder,
+			subtree: OnceLock::new(),
+			consumed: OnceLock::new(),
+			phantom: PhantomData,
+		}
+	}
+
+	fn subtree(&self) -> &E {
+		self.subtree
+			.get_or_init(|| Arc::new((self.builder)(&self.key)))
+	}
+}
+
+impl<
+	State: ValidState,
+	K: PartialEq + Clone + Send + Sync + 'static,
+	E: Element<State>,
+	F: Fn(&K) -> E + Send + Sync + 'static,
+> ElementDiffer<State> for Memo<State, K, E, F>
+{
+	fn create_inner_recursive(
+		&self,
+		inner_key: u64,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		context.start_consuming();
+		self.subtree().create_inner_recursive(
+			inner_key,
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+		let _ = self.consumed.set(context.finish_consuming());
+	}
+
+	fn frame_recursive(
+		&self,
+		context: &Context,
+		info: &FrameInfo,
+		state: &mut State,
+		inner_map: &mut ElementInnerMap,
+	) {
+		if let Some(subtree) = self.subtree.get() {
+			subtree.frame_recursive(context, info, state, inner_map);
+		}
+	}
+
+	fn diff_same_type(
+		&self,
+		inner_key: u64,
+		old: &Self,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		let Some(old_subtree) = old.subtree.get() else {
+			self.create_inner_recursive(
+				inner_key,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			);
+			return;
+		};
+
+		let old_consumed = old.consumed.get().cloned().unwrap_or_default();
+		if self.key == old.key && !context.providers_changed(&old_consumed) {
+			// Same inputs, so the old subtree (with all its inner keys) is still correct
+			let _ = self.subtree.set(old_subtree.clone());
+			context.consume_all(&old_consumed);
+			let _ = self.consumed.set(old_consumed);
+			return;
+		}
+
+		context.start_consuming();
+		self.subtree().diff_same_type(
+			inner_key,
+			old_subtree,
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+		let _ = self.consumed.set(context.finish_consuming());
+	}
+
+	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
+		if let Some(subtree) = self.subtree.get() {
+			subtree.destroy_inner_recursive(inner_map);
+		}
+	}
+
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		// measuring a subtree that isn't built yet would run the builder on every reify
+		self.subtree.get()?.bounds_hint()
+	}
+
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		match self.subtree.get() {
+			Some(subtree) => subtree.blueprint(context, nodes),
+			None => (self.builder)(&self.key).blueprint(context, nodes),
+		}
+	}
+}
+
+impl<
+	State: ValidState,
+	K: PartialEq + Clone + Send + Sync + 'static,
+	E: Element<State>,
+	F: Fn(&K) -> E + Send + Sync + 'static,
+> Element<State> for Memo<State, K, E, F>
+{
+}
+
+#[tokio::test]
+async fn asteroids_memo() {
+	use crate::{
+		Projector, Reify, Transformable,
+		custom::CustomElement,
+		elements::{Spatial, Text},
+	};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	static BUILDS: AtomicUsize = AtomicUsize::new(0);
+
+	struct TestState {
+		label: String,
+		offset: f32,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Spatial::default()
+				.pos([self.offset, 0.0, 0.0])
+				.build()
+				.child(Memo::new(self.label.clone(), |label: &String| {
+					BUILDS.fetch_add(1, Ordering::Relaxed);
+					Text::new(label).build()
+				}))
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let mut state = TestState {
+		label: "hello".to_string(),
+		offset: 0.0,
+	};
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	assert_eq!(BUILDS.load(Ordering::Relaxed), 1);
+
+	// unrelated change, memoized subtree is reused
+	state.offset = 0.5;
+	projector.update(&context, &mut state);
+	projector.update(&context, &mut state);
+	assert_eq!(BUILDS.load(Ordering::Relaxed), 1);
+	let scene = projector.scene().unwrap();
+	assert!(scene.find("Text").next().unwrap().debug.contains("hello"));
+
+	state.label = "world".to_string();
+	projector.update(&context, &mut state);
+	assert_eq!(BUILDS.load(Ordering::Relaxed), 2);
+	let scene = projector.scene().unwrap();
+	assert!(scene.find("Text").next().unwrap().debug.contains("world"));
+}
+
+#[tokio::test]
+async fn asteroids_memo_in_stack() {
+	use crate::{
+		Projector, Reify,
+		custom::CustomElement,
+		elements::{Stack, Text},
+	};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	static BUILDS: AtomicUsize = AtomicUsize::new(0);
+
+	struct TestState {
+		label: String,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Stack::column()
+				.child(Memo::new(self.label.clone(), |label: &String| {
+					BUILDS.fetch_add(1, Ordering::Relaxed);
+					Text::new(label).build()
+				}))
+				.child_sized(
+					[0.1, 0.01],
+					Memo::new(self.label.clone(), |label: &String| {
+						BUILDS.fetch_add(1, Ordering::Relaxed);
+						Text::new(label).build()
+					}),
+				)
+				.build()
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let mut state = TestState {
+		label: "hello".to_string(),
+	};
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	assert_eq!(BUILDS.load(Ordering::Relaxed), 2);
+
+	// laying out the stack doesn't build the memos again
+	projector.update(&context, &mut state);
+	projector.update(&context, &mut state);
+	assert_eq!(BUILDS.load(Ordering::Relaxed), 2);
+}
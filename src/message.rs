@@ -0,0 +1,19 @@
+use crate::ValidState;
+use std::fmt::Debug;
+
+/// Elm-style state updates: elements built with `emit` (e.g. [`crate::elements::Button::emit`])
+/// send a [`Update::Message`] instead of calling a closure, so every change to the state goes through [`Update::update`].
+///
+/// Opt-in, closures and messages can be mixed in the same tree.
+pub trait Update: ValidState {
+	type Message: Debug + Send + Sync + 'static;
+
+	/// Apply `message` to the state, this is the place to filter or record messages.
+	fn update(&mut self, message: Self::Message);
+
+	/// What elements call with their messages, logs them before passing them on to [`Update::update`].
+	fn dispatch(&mut self, message: Self::Message) {
+		tracing::debug!(?message, "dispatching message");
+		self.update(message);
+	}
+}
@@ -0,0 +1,338 @@
+use crate::{
+	Context, Element, ValidState,
+	blueprint::BlueprintNode,
+	element::{ElementDiffer, ParentSpace},
+	inner::ElementInnerMap,
+	resource::ResourceRegistry,
+};
+use stardust_xr_fusion::{root::FrameInfo, spatial::BoundingBox};
+use std::{
+	marker::PhantomData,
+	path::Path,
+	sync::{Arc, OnceLock},
+};
+
+/// Makes `value` available to every element inside `content` through [`Context::provided`],
+/// e.g. a theme, a font or a shared service, without threading it through every `reify`.
+pub struct Provider<State: ValidState, T: PartialEq + Send + Sync + 'static, C: Element<State>> {
+	value: Arc<T>,
+	content: C,
+	phantom: PhantomData<State>,
+}
+impl<State: ValidState, T: PartialEq + Send + Sync + 'static, C: Element<State>>
+	Provider<State, T, C>
+{
+	pub fn new(value: T, content: C) -> Self {
+		Provider {
+			value: Arc::new(value),
+			content,
+			phantom: PhantomData,
+		}
+	}
+}
+
+impl<State: ValidState, T: PartialEq + Send + Sync + 'static, C: Element<State>>
+	ElementDiffer<State> for Provider<State, T, C>
+{
+	fn create_inner_recursive(
+		&self,
+		inner_key: u64,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		context.push_provider(self.value.clone(), false);
+		self.content.create_inner_recursive(
+			inner_key,
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+		context.pop_provider();
+	}
+
+	fn frame_recursive(
+		&self,
+		context: &Context,
+		info: &FrameInfo,
+		state: &mut State,
+		inner_map: &mut ElementInnerMap,
+	) {
+		context.push_provider(self.value.clone(), false);
+		self.content
+			.frame_recursive(context, info, state, inner_map);
+		context.pop_provider();
+	}
+
+	fn diff_same_type(
+		&self,
+		inner_key: u64,
+		old: &Self,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		context.push_provider(self.value.clone(), self.value != old.value);
+		self.content.diff_same_type(
+			inner_key,
+			&old.content,
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+		context.pop_provider();
+	}
+
+	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
+		self.content.destroy_inner_recursive(inner_map);
+	}
+
+	fn bounds_hint(&self) -> Option<BoundingBox> {
+		self.content.bounds_hint()
+	}
+
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		context.push_provider(self.value.clone(), false);
+		self.content.blueprint(context, nodes);
+		context.pop_provider();
+	}
+}
+
+impl<State: ValidState, T: PartialEq + Send + Sync + 'static, C: Element<State>> Element<State>
+	for Provider<State, T, C>
+{
+}
+
+/// A subtree built from the nearest provided `T`, rebuilt and diffed every update so it follows changes to the value.
+pub struct Consumer<
+	State: ValidState,
+	T: Send + Sync + 'static,
+	E: Element<State>,
+	F: Fn(Option<&T>) -> E + Send + Sync + 'static,
+> {
+	builder: F,
+	subtree: OnceLock<E>,
+	phantom: PhantomData<(State, fn(&T))>,
+}
+impl<
+	State: ValidState,
+	T: Send + Sync + 'static,
+	E: Element<State>,
+	F: Fn(Option<&T>) -> E + Send + Sync + 'static,
+> Consumer<State, T, E, F>
+{
+	pub fn new(builder: F) -> Self {
+		Consumer {
+			builder,
+			subtree: OnceLock::new(),
+			phantom: PhantomData,
+		}
+	}
+
+	fn subtree(&self, context: &Context) -> &E {
+		self.subtree
+			.get_or_init(|| (self.builder)(context.provided::<T>().as_deref()))
+	}
+}
+
+impl<
+	State: ValidState,
+	T: Send + Sync + 'static,
+	E: Element<State>,
+	F: Fn(Option<&T>) -> E + Send + Sync + 'static,
+> ElementDiffer<State> for Consumer<State, T, E, F>
+{
+	fn create_inner_recursive(
+		&self,
+		inner_key: u64,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		self.subtree(context).create_inner_recursive(
+			inner_key,
+			context,
+			parent_space,
+			element_path,
+			inner_map,
+			resources,
+		);
+	}
+
+	fn frame_recursive(
+		&self,
+		context: &Context,
+		info: &FrameInfo,
+		state: &mut State,
+		inner_map: &mut ElementInnerMap,
+	) {
+		if let Some(subtree) = self.subtree.get() {
+			subtree.frame_recursive(context, info, state, inner_map);
+		}
+	}
+
+	fn diff_same_type(
+		&self,
+		inner_key: u64,
+		old: &Self,
+		context: &Context,
+		parent_space: &ParentSpace,
+		element_path: &Path,
+		inner_map: &mut ElementInnerMap,
+		resources: &mut ResourceRegistry,
+	) {
+		match old.subtree.get() {
+			Some(old_subtree) => self.subtree(context).diff_same_type(
+				inner_key,
+				old_subtree,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			),
+			None => self.create_inner_recursive(
+				inner_key,
+				context,
+				parent_space,
+				element_path,
+				inner_map,
+				resources,
+			),
+		}
+	}
+
+	fn destroy_inner_recursive(&self, inner_map: &mut ElementInnerMap) {
+		if let Some(subtree) = self.subtree.get() {
+			subtree.destroy_inner_recursive(inner_map);
+		}
+	}
+
+	fn blueprint(&self, context: &Context, nodes: &mut Vec<BlueprintNode>) {
+		match self.subtree.get() {
+			Some(subtree) => subtree.blueprint(context, nodes),
+			None => (self.builder)(context.provided::<T>().as_deref()).blueprint(context, nodes),
+		}
+	}
+}
+
+impl<
+	State: ValidState,
+	T: Send + Sync + 'static,
+	E: Element<State>,
+	F: Fn(Option<&T>) -> E + Send + Sync + 'static,
+> Element<State> for Consumer<State, T, E, F>
+{
+}
+
+#[tokio::test]
+async fn asteroids_provider() {
+	use crate::{
+		Memo, Projector, Reify,
+		custom::CustomElement,
+		elements::{Spatial, Text},
+	};
+
+	#[derive(PartialEq)]
+	struct Theme {
+		greeting: &'static str,
+	}
+
+	struct TestState {
+		greeting: &'static str,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Provider::new(
+				Theme {
+					greeting: self.greeting,
+				},
+				Spatial::default().build().child(Memo::new((), |_| {
+					Consumer::new(|theme: Option<&Theme>| {
+						Text::new(theme.map(|theme| theme.greeting).unwrap_or_default()).build()
+					})
+				})),
+			)
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let mut state = TestState { greeting: "hello" };
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	let scene = projector.scene().unwrap();
+	assert!(scene.find("Text").next().unwrap().debug.contains("hello"));
+
+	// the consumer gets re-diffed even though the memo above it has the same key
+	state.greeting = "howdy";
+	projector.update(&context, &mut state);
+	let scene = projector.scene().unwrap();
+	assert!(scene.find("Text").next().unwrap().debug.contains("howdy"));
+	assert!(context.provided::<Theme>().is_none());
+}
+
+#[tokio::test]
+async fn asteroids_provider_changes_per_type() {
+	use crate::{
+		Memo, Projector, Reify,
+		custom::CustomElement,
+		elements::{Spatial, Text},
+	};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	static CONSUMES: AtomicUsize = AtomicUsize::new(0);
+
+	#[derive(PartialEq)]
+	struct Theme(&'static str);
+	#[derive(PartialEq)]
+	struct Locale(&'static str);
+
+	struct TestState {
+		theme: &'static str,
+		locale: &'static str,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Provider::new(
+				Theme(self.theme),
+				Provider::new(
+					Locale(self.locale),
+					Spatial::default().build().child(Memo::new((), |_| {
+						Consumer::new(|theme: Option<&Theme>| {
+							CONSUMES.fetch_add(1, Ordering::Relaxed);
+							Text::new(theme.map(|theme| theme.0).unwrap_or_default()).build()
+						})
+					})),
+				),
+			)
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let mut state = TestState {
+		theme: "dark",
+		locale: "en",
+	};
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	assert_eq!(CONSUMES.load(Ordering::Relaxed), 1);
+
+	// the memoized subtree never reads the locale, so it stays as it is
+	state.locale = "fr";
+	projector.update(&context, &mut state);
+	assert_eq!(CONSUMES.load(Ordering::Relaxed), 1);
+
+	state.theme = "light";
+	projector.update(&context, &mut state);
+	assert_eq!(CONSUMES.load(Ordering::Relaxed), 2);
+	let scene = projector.scene().unwrap();
+	assert!(scene.find("Text").next().unwrap().debug.contains("light"));
+}
@@ -0,0 +1,290 @@
+//! Records a client's frames and state when `ASTEROIDS_RECORD` is set to a file path,
+//! so a bug report can come with something to [`replay`] without a server.
+//!
+//! A recording is one RON [`RecordEntry`] per line: the state the client started with,
+//! then every frame along with the state after it whenever it changed,
+//! both serialized in the client's [`ClientState::FORMAT`].
+//!
+//! Only snapshots of the state are recorded, not the input events or messages that changed it.
+//! Replaying reruns the frame timing, timers and animations, and shows on which frame
+//! the state changed in a way that replay alone didn't reproduce and what it changed to,
+//! but it can't replay the input itself.
+
+use crate::{FormatError, client::ClientState};
+use serde::{Deserialize, Serialize};
+use stardust_xr_fusion::root::FrameInfo;
+use std::{
+	fmt::Display,
+	fs::File,
+	io::{BufWriter, Write},
+	path::Path,
+};
+#[cfg(any(test, feature = "headless"))]
+use {
+	crate::{Context, History, Projector},
+	std::io::{BufRead, BufReader},
+};
+
+/// One line of a recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecordEntry {
+	/// Serialized state right before the first frame
+	Initial(Vec<u8>),
+	Frame {
+		delta: f32,
+		elapsed: f32,
+		/// Serialized state after this frame's callbacks and update, `None` if it didn't change
+		state: Option<Vec<u8>>,
+	},
+}
+
+/// Writes the recording while the client runs.
+pub(crate) struct Recorder {
+	file: BufWriter<File>,
+	last_state: Vec<u8>,
+}
+impl Recorder {
+	/// Start recording to the path in `ASTEROIDS_RECORD`, if it's set.
+	pub(crate) fn from_env<State: ClientState>(state: &State) -> Option<Self> {
+		let path = std::env::var_os("ASTEROIDS_RECORD")?;
+		Self::create(Path::new(&path), state)
+			.inspect_err(|error| tracing::warn!("Couldn't create recording: {error}"))
+			.ok()
+	}
+	fn create<State: ClientState>(path: &Path, state: &State) -> std::io::Result<Self> {
+		let mut recorder = Recorder {
+			file: BufWriter::new(File::create(path)?),
+			last_state: State::FORMAT
+				.serialize(state)
+				.map_err(std::io::Error::other)?,
+		};
+		recorder.write(&RecordEntry::Initial(recorder.last_state.clone()));
+		Ok(recorder)
+	}
+	/// Record the frames handled since the last update, with the state after it on the last one
+	pub(crate) fn record<State: ClientState>(&mut self, frames: &[FrameInfo], state: &State) {
+		let Some((last, dropped)) = frames.split_last() else {
+			return;
+		};
+		for frame in dropped {
+			self.write(&RecordEntry::Frame {
+				delta: frame.delta,
+				elapsed: frame.elapsed,
+				state: None,
+			});
+		}
+		let state = State::FORMAT
+			.serialize(state)
+			.ok()
+			.filter(|state| *state != self.last_state);
+		if let Some(state) = &state {
+			self.last_state.clone_from(state);
+		}
+		self.write(&RecordEntry::Frame {
+			delta: last.delta,
+			elapsed: last.elapsed,
+			state,
+		});
+	}
+	fn write(&mut self, entry: &RecordEntry) {
+		let Ok(line) = ron::to_string(entry) else {
+			return;
+		};
+		// flush every frame so a crash still leaves a usable recording
+		let _ = writeln!(self.file, "{line}").and_then(|_| self.file.flush());
+	}
+}
+
+/// A frame where replaying didn't end up with the recorded state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+	/// Index of the frame in the recording, starting at 0
+	pub frame: usize,
+	/// Serialized in the client's [`ClientState::FORMAT`], like the recording
+	pub expected: Vec<u8>,
+	pub actual: Vec<u8>,
+}
+
+/// What happened when replaying a recording.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReplayReport {
+	pub frames: usize,
+	/// Every frame that diverged, after each one the replay continues from the recorded state.
+	/// Input isn't recorded, so a frame where input changed the state shows up here too.
+	pub divergences: Vec<Divergence>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+	Io(std::io::Error),
+	/// A line that isn't a [`RecordEntry`]
+	Parse {
+		line: usize,
+		error: ron::Error,
+	},
+	/// A recorded state that doesn't deserialize
+	State {
+		line: usize,
+		error: FormatError,
+	},
+	/// The recording doesn't start with [`RecordEntry::Initial`]
+	MissingInitialState,
+	Context(zbus::Error),
+}
+impl Display for ReplayError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ReplayError::Io(error) => write!(f, "couldn't read recording: {error}"),
+			ReplayError::Parse { line, error } => {
+				write!(f, "invalid recording at line {line}: {error}")
+			}
+			ReplayError::State { line, error } => {
+				write!(f, "invalid state in recording at line {line}: {error}")
+			}
+			ReplayError::MissingInitialState => {
+				write!(f, "recording doesn't start with the initial state")
+			}
+			ReplayError::Context(error) => write!(f, "couldn't create headless context: {error}"),
+		}
+	}
+}
+impl std::error::Error for ReplayError {}
+
+/// Feed a recording back through a headless [`Projector`], comparing the state after every frame to the recorded one.
+///
+/// Only the main projector is replayed, not [`ClientState::views`]. Needs the `headless` feature.
+#[cfg(any(test, feature = "headless"))]
+pub async fn replay<State: ClientState>(
+	path: impl AsRef<Path>,
+) -> Result<ReplayReport, ReplayError> {
+	let file = File::open(path).map_err(ReplayError::Io)?;
+	let mut entries = BufReader::new(file)
+		.lines()
+		.enumerate()
+		.filter(|(_, line)| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+		.map(|(index, line)| {
+			let line_number = index + 1;
+			let entry = ron::from_str::<RecordEntry>(&line.map_err(ReplayError::Io)?);
+			entry
+				.map_err(|error| ReplayError::Parse {
+					line: line_number,
+					error: error.code,
+				})
+				.map(|entry| (line_number, entry))
+		});
+	let parse_state = |line: usize, state: &[u8]| {
+		State::FORMAT
+			.deserialize::<State>(state)
+			.map_err(|error| ReplayError::State { line, error })
+	};
+
+	let (line, RecordEntry::Initial(initial)) =
+		entries.next().ok_or(ReplayError::MissingInitialState)??
+	else {
+		return Err(ReplayError::MissingInitialState);
+	};
+	let mut state = parse_state(line, &initial)?;
+	let mut expected = initial;
+
+	let context = Context::headless().await.map_err(ReplayError::Context)?;
+	History::apply(&mut state);
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	let mut report = ReplayReport::default();
+	for entry in entries {
+		let (line, entry) = entry?;
+		let RecordEntry::Frame {
+			delta,
+			elapsed,
+			state: recorded,
+		} = entry
+		else {
+			continue;
+		};
+		let info = FrameInfo { delta, elapsed };
+		// same order as `client::run`
+		state.on_frame(&info);
+		projector.frame(&context, &info, &mut state);
+		History::apply(&mut state);
+		projector.update(&context, &mut state);
+
+		if let Some(recorded) = recorded {
+			expected = recorded;
+		}
+		let actual = State::FORMAT.serialize(&state).unwrap_or_default();
+		if actual != expected {
+			report.divergences.push(Divergence {
+				frame: report.frames,
+				expected: expected.clone(),
+				actual,
+			});
+			state = parse_state(line, &expected)?;
+			projector.update(&context, &mut state);
+		}
+		report.frames += 1;
+	}
+	Ok(report)
+}
+
+#[tokio::test]
+async fn asteroids_record_replay() {
+	use crate::{CustomElement, Element, Reify, elements::Timeout};
+	use std::time::Duration;
+
+	#[derive(Default, Serialize, Deserialize)]
+	struct TestState {
+		elapsed: f32,
+		timed_out: bool,
+		dial: f32,
+	}
+	impl crate::Migrate for TestState {
+		type Old = Self;
+	}
+	impl ClientState for TestState {
+		const APP_ID: &'static str = "org.asteroids.record";
+
+		fn on_frame(&mut self, info: &FrameInfo) {
+			self.elapsed = info.elapsed;
+		}
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Timeout::new(Duration::from_secs(1), |state: &mut Self| {
+				state.timed_out = true
+			})
+		}
+	}
+
+	let path = std::env::temp_dir().join(format!("asteroids_recording_{}.ron", std::process::id()));
+	let mut state = TestState {
+		dial: 0.5,
+		..Default::default()
+	};
+	let mut recorder = Recorder::create(&path, &state).unwrap();
+	for frame in 1..=4 {
+		let info = FrameInfo {
+			delta: 0.5,
+			elapsed: frame as f32 * 0.5,
+		};
+		state.on_frame(&info);
+		state.timed_out |= info.elapsed >= 1.0;
+		// what a dial callback would do on the live server
+		if frame == 3 {
+			state.dial = 0.0;
+		}
+		recorder.record(&[info], &state);
+	}
+	drop(recorder);
+
+	let report = replay::<TestState>(&path).await.unwrap();
+	std::fs::remove_file(&path).unwrap();
+	assert_eq!(report.frames, 4);
+	// the timeout replays the same, the dial can't be turned without a server
+	let [divergence] = report.divergences.as_slice() else {
+		panic!("expected one divergence, got {:?}", report.divergences);
+	};
+	assert_eq!(divergence.frame, 2);
+	let expected = String::from_utf8(divergence.expected.clone()).unwrap();
+	let actual = String::from_utf8(divergence.actual.clone()).unwrap();
+	assert!(expected.contains("dial:0.0"));
+	assert!(actual.contains("dial:0.5"));
+}
@@ -3,15 +3,127 @@ use std::any::{Any, TypeId};
 
 use crate::{CustomElement, ValidState};
 
+/// Which resource an element uses: one per element type per [`crate::elements::ResourceScope`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct ResourceKey {
+	scope: u64,
+	element: TypeId,
+}
+
+struct ResourceEntry {
+	resource: Box<dyn Any>,
+	/// Live elements using this resource, it's dropped once this reaches 0
+	users: usize,
+}
+
 #[derive(Default)]
-pub(crate) struct ResourceRegistry(FxHashMap<TypeId, Box<dyn Any>>);
+pub(crate) struct ResourceRegistry {
+	resources: FxHashMap<ResourceKey, ResourceEntry>,
+	/// Scopes of the `ResourceScope`s above the element currently being created, innermost last
+	scopes: Vec<u64>,
+}
 impl ResourceRegistry {
-	pub fn get<State: ValidState, E: CustomElement<State>>(&mut self) -> &mut E::Resource {
-		let type_id = TypeId::of::<E::Resource>();
-		self.0
-			.entry(type_id)
-			.or_insert_with(|| Box::new(E::Resource::default()))
+	/// Start using the resource of `E` in the current scope, creating it if nothing uses it yet
+	pub fn acquire<State: ValidState, E: CustomElement<State>>(&mut self) -> ResourceKey {
+		let key = ResourceKey {
+			scope: self.scopes.last().copied().unwrap_or_default(),
+			element: TypeId::of::<E>(),
+		};
+		self.entry::<State, E>(key).users += 1;
+		key
+	}
+	pub fn get<State: ValidState, E: CustomElement<State>>(
+		&mut self,
+		key: ResourceKey,
+	) -> &mut E::Resource {
+		self.entry::<State, E>(key)
+			.resource
 			.downcast_mut::<E::Resource>()
 			.unwrap()
 	}
+	/// Stop using these resources, dropping the ones nothing uses anymore
+	pub fn release(&mut self, keys: impl IntoIterator<Item = ResourceKey>) {
+		for key in keys {
+			let Some(entry) = self.resources.get_mut(&key) else {
+				continue;
+			};
+			entry.users = entry.users.saturating_sub(1);
+			if entry.users == 0 {
+				self.resources.remove(&key);
+			}
+		}
+	}
+
+	pub fn push_scope(&mut self, scope: u64) {
+		self.scopes.push(scope);
+	}
+	pub fn pop_scope(&mut self) {
+		self.scopes.pop();
+	}
+
+	fn entry<State: ValidState, E: CustomElement<State>>(
+		&mut self,
+		key: ResourceKey,
+	) -> &mut ResourceEntry {
+		self.resources.entry(key).or_insert_with(|| ResourceEntry {
+			resource: Box::new(E::Resource::default()),
+			users: 0,
+		})
+	}
+}
+
+#[test]
+fn asteroids_resource_registry() {
+	use crate::{Context, CreateInnerInfo, LocalTransform};
+	use stardust_xr_fusion::spatial::SpatialRef;
+
+	#[derive(Debug)]
+	struct Cached<const N: usize>;
+	impl<const N: usize> CustomElement<()> for Cached<N> {
+		type Inner = ();
+		type Resource = Vec<u8>;
+		type Error = String;
+
+		fn create_inner(
+			&self,
+			_context: &Context,
+			_info: CreateInnerInfo,
+			_resource: &mut Self::Resource,
+		) -> Result<Self::Inner, Self::Error> {
+			Ok(())
+		}
+		fn diff(
+			&self,
+			_context: &Context,
+			_old_self: &Self,
+			_inner: &mut (),
+			_resource: &mut Self::Resource,
+		) {
+		}
+		fn spatial_aspect(&self, _inner: &Self::Inner) -> SpatialRef {
+			unreachable!()
+		}
+	}
+
+	impl<const N: usize> LocalTransform for Cached<N> {}
+
+	let mut registry = ResourceRegistry::default();
+	let a = registry.acquire::<(), Cached<0>>();
+	registry.get::<(), Cached<0>>(a).push(1);
+	// same resource type, different element type
+	let b = registry.acquire::<(), Cached<1>>();
+	assert!(registry.get::<(), Cached<1>>(b).is_empty());
+
+	registry.push_scope(42);
+	let scoped = registry.acquire::<(), Cached<0>>();
+	registry.pop_scope();
+	assert!(registry.get::<(), Cached<0>>(scoped).is_empty());
+
+	let a_again = registry.acquire::<(), Cached<0>>();
+	assert_eq!(a, a_again);
+	registry.release([a]);
+	assert_eq!(registry.get::<(), Cached<0>>(a_again), &[1]);
+	registry.release([a_again]);
+	// nothing uses it anymore so it starts over
+	assert!(registry.get::<(), Cached<0>>(a).is_empty());
 }
@@ -0,0 +1,222 @@
+use crate::{ValidState, custom::CustomElement, element::element_type_name};
+use rustc_hash::FxHashMap;
+use stardust_xr_fusion::spatial::Transform;
+use std::path::{Path, PathBuf};
+
+/// A node in the in-memory scene graph of a headless [`crate::Projector`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneNode {
+	/// Element name without the namespace or generics, e.g. `Button`
+	pub element_type: &'static str,
+	/// Unique path of this element, e.g. `/Spatial_1234/Button_5678`
+	pub path: PathBuf,
+	/// Inner key the element is stored under, stable across updates
+	pub key: u64,
+	/// Path of the element this one is parented under, or the projector's root path
+	pub parent: PathBuf,
+	/// The element's local transform, if it reports one
+	pub transform: Option<Transform>,
+	/// `Debug` output of the element declaration
+	pub debug: String,
+}
+impl SceneNode {
+	pub(crate) fn new<State: ValidState, E: CustomElement<State>>(
+		element: &E,
+		key: u64,
+		path: &Path,
+		parent: &Path,
+	) -> Self {
+		SceneNode {
+			element_type: element_type_name::<E>(),
+			path: path.to_path_buf(),
+			key,
+			parent: parent.to_path_buf(),
+			transform: element.local_transform(),
+			debug: format!("{element:?}"),
+		}
+	}
+	pub(crate) fn update<State: ValidState, E: CustomElement<State>>(&mut self, element: &E) {
+		self.transform = element.local_transform();
+		self.debug = format!("{element:?}");
+	}
+}
+
+/// Every element currently alive in a headless [`crate::Projector`], so tests can check the tree without a server.
+/// Inspected projectors keep one too, see [`crate::inspector`].
+#[derive(Debug, Default)]
+pub struct Scene {
+	nodes: FxHashMap<u64, SceneNode>,
+}
+impl Scene {
+	pub(crate) fn insert(&mut self, inner_key: u64, node: SceneNode) {
+		self.nodes.insert(inner_key, node);
+	}
+	pub(crate) fn get_mut(&mut self, inner_key: u64) -> Option<&mut SceneNode> {
+		self.nodes.get_mut(&inner_key)
+	}
+	pub(crate) fn contains(&self, inner_key: u64) -> bool {
+		self.nodes.contains_key(&inner_key)
+	}
+	pub(crate) fn remove(&mut self, inner_key: u64) {
+		self.nodes.remove(&inner_key);
+	}
+
+	pub fn len(&self) -> usize {
+		self.nodes.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+	/// All nodes, in no particular order
+	pub fn nodes(&self) -> impl Iterator<Item = &SceneNode> {
+		self.nodes.values()
+	}
+	pub fn get(&self, path: impl AsRef<Path>) -> Option<&SceneNode> {
+		let path = path.as_ref();
+		self.nodes().find(|node| node.path == path)
+	}
+	/// Nodes parented directly under `path`
+	pub fn children(&self, path: impl AsRef<Path>) -> impl Iterator<Item = &SceneNode> {
+		let path = path.as_ref().to_path_buf();
+		self.nodes().filter(move |node| node.parent == path)
+	}
+	/// Nodes of the given element type, e.g. `scene.find("Text")`
+	pub fn find<'a>(&'a self, element_type: &'a str) -> impl Iterator<Item = &'a SceneNode> {
+		self.nodes()
+			.filter(move |node| node.element_type == element_type)
+	}
+}
+
+#[tokio::test]
+async fn asteroids_headless_projector() {
+	use crate::{
+		Context, Projector, Reify, Transformable,
+		custom::CustomElement,
+		elements::{Spatial, Text},
+	};
+
+	struct TestState {
+		offset: f32,
+		label: Option<String>,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl crate::Element<Self> {
+			Spatial::default()
+				.pos([self.offset, 0.0, 0.0])
+				.build()
+				.maybe_child(self.label.as_ref().map(|label| Text::new(label).build()))
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let mut state = TestState {
+		offset: 0.0,
+		label: Some("hello".to_string()),
+	};
+	let mut projector = Projector::headless(&state, &context, "/".into());
+
+	let scene = projector.scene().unwrap();
+	assert_eq!(scene.len(), 2);
+	let spatial = scene.find("Spatial").next().unwrap();
+	assert_eq!(spatial.parent, Path::new("/"));
+	let text = scene.find("Text").next().unwrap();
+	assert_eq!(text.parent, spatial.path);
+	assert!(text.debug.contains("hello"));
+
+	state.offset = 0.5;
+	state.label = None;
+	projector.update(&context, &mut state);
+
+	let scene = projector.scene().unwrap();
+	assert_eq!(scene.len(), 1);
+	let spatial = scene.find("Spatial").next().unwrap();
+	assert_eq!(
+		spatial.transform.unwrap().translation,
+		Some([0.5, 0.0, 0.0].into())
+	);
+	assert!(scene.find("Text").next().is_none());
+}
+
+#[tokio::test]
+async fn asteroids_headless_elements_run() {
+	use crate::{Context, CreateInnerInfo, LocalTransform, Projector, Reify};
+	use stardust_xr_fusion::{
+		node::NodeError,
+		root::FrameInfo,
+		spatial::{Spatial, SpatialRef},
+	};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	static CREATED: AtomicUsize = AtomicUsize::new(0);
+	static DIFFED: AtomicUsize = AtomicUsize::new(0);
+
+	#[derive(Debug)]
+	struct Counter(u32);
+	impl CustomElement<TestState> for Counter {
+		type Inner = Spatial;
+		type Resource = ();
+		type Error = NodeError;
+
+		fn create_inner(
+			&self,
+			_context: &Context,
+			info: CreateInnerInfo,
+			_resource: &mut Self::Resource,
+		) -> Result<Self::Inner, Self::Error> {
+			CREATED.fetch_add(1, Ordering::Relaxed);
+			Spatial::create(info.parent_space, Transform::none())
+		}
+		fn diff(
+			&self,
+			_context: &Context,
+			old_self: &Self,
+			_inner: &mut Self::Inner,
+			_resource: &mut (),
+		) {
+			if self.0 != old_self.0 {
+				DIFFED.fetch_add(1, Ordering::Relaxed);
+			}
+		}
+		fn frame(
+			&self,
+			_context: &Context,
+			_info: &FrameInfo,
+			state: &mut TestState,
+			_inner: &mut Self::Inner,
+		) {
+			state.frames += 1;
+		}
+		fn spatial_aspect(&self, inner: &Self::Inner) -> SpatialRef {
+			inner.clone().as_spatial_ref()
+		}
+	}
+
+	impl LocalTransform for Counter {}
+
+	#[derive(Default)]
+	struct TestState {
+		count: u32,
+		frames: u32,
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl crate::Element<Self> {
+			Counter(self.count).build()
+		}
+	}
+
+	let context = Context::headless().await.unwrap();
+	let mut state = TestState::default();
+	let mut projector = Projector::headless(&state, &context, "/".into());
+	assert_eq!(CREATED.load(Ordering::Relaxed), 1);
+
+	state.count += 1;
+	projector.update(&context, &mut state);
+	assert_eq!(DIFFED.load(Ordering::Relaxed), 1);
+	let info = FrameInfo {
+		delta: 0.1,
+		elapsed: 0.1,
+	};
+	projector.frame(&context, &info, &mut state);
+	assert_eq!(state.frames, 1);
+	assert_eq!(CREATED.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,141 @@
+use super::{DeserializeAny, Migrate, Versioned};
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt::Display;
+
+/// How a `ClientState` gets saved, for both `SaveState` data and the initial state file.
+///
+/// RON is always available, the others are behind the feature of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Format {
+	#[default]
+	Ron,
+	#[cfg(feature = "json")]
+	Json,
+	#[cfg(feature = "toml")]
+	Toml,
+	/// Compact and fast for large states, but not meant to be edited by hand
+	#[cfg(feature = "bincode")]
+	Bincode,
+}
+impl Format {
+	/// Extension of files in this format, without the dot
+	pub fn extension(self) -> &'static str {
+		match self {
+			Format::Ron => "ron",
+			#[cfg(feature = "json")]
+			Format::Json => "json",
+			#[cfg(feature = "toml")]
+			Format::Toml => "toml",
+			#[cfg(feature = "bincode")]
+			Format::Bincode => "bin",
+		}
+	}
+	pub fn serialize<T: Serialize>(self, value: &T) -> Result<Vec<u8>, FormatError> {
+		match self {
+			Format::Ron => Ok(ron::to_string(value)
+				.map_err(FormatError::new)?
+				.into_bytes()),
+			#[cfg(feature = "json")]
+			Format::Json => serde_json::to_vec(value).map_err(FormatError::new),
+			#[cfg(feature = "toml")]
+			Format::Toml => Ok(toml::to_string(value)
+				.map_err(FormatError::new)?
+				.into_bytes()),
+			#[cfg(feature = "bincode")]
+			Format::Bincode => bincode::serialize(value).map_err(FormatError::new),
+		}
+	}
+	pub fn deserialize<T: DeserializeOwned>(self, data: &[u8]) -> Result<T, FormatError> {
+		match self {
+			Format::Ron => ron::de::from_bytes(data).map_err(FormatError::new),
+			#[cfg(feature = "json")]
+			Format::Json => serde_json::from_slice(data).map_err(FormatError::new),
+			#[cfg(feature = "toml")]
+			Format::Toml => {
+				let text = std::str::from_utf8(data).map_err(FormatError::new)?;
+				toml::from_str(text).map_err(FormatError::new)
+			}
+			#[cfg(feature = "bincode")]
+			Format::Bincode => bincode::deserialize(data).map_err(FormatError::new),
+		}
+	}
+}
+
+/// An error from whichever library a [`Format`] uses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatError(String);
+impl FormatError {
+	fn new(error: impl Display) -> Self {
+		FormatError(error.to_string())
+	}
+}
+impl Display for FormatError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+impl std::error::Error for FormatError {}
+
+/// Serialized state in some [`Format`], for `Migrate::deserialize_with_migrate`.
+pub(crate) struct SavedState {
+	pub format: Format,
+	pub data: Vec<u8>,
+}
+impl DeserializeAny<'_, FormatError> for SavedState {
+	fn deserialize<T: DeserializeOwned>(&self) -> Result<T, FormatError> {
+		self.format.deserialize(&self.data)
+	}
+}
+impl SavedState {
+	/// Serialize `state` along with its version so it can be migrated later
+	pub(crate) fn serialize<T: Migrate + Serialize>(
+		format: Format,
+		state: &T,
+	) -> Result<Self, FormatError> {
+		Ok(SavedState {
+			format,
+			data: format.serialize(&Versioned::new(state))?,
+		})
+	}
+}
+
+#[tokio::test]
+async fn asteroids_state_formats() {
+	use serde::Deserialize;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Strokes {
+		points: Vec<[f32; 3]>,
+	}
+	impl Migrate for Strokes {
+		type Old = Self;
+	}
+
+	let strokes = Strokes {
+		points: vec![[0.0, 1.0, 2.0], [3.0, 4.0, 5.0]],
+	};
+	let formats = [
+		Format::Ron,
+		#[cfg(feature = "json")]
+		Format::Json,
+		#[cfg(feature = "toml")]
+		Format::Toml,
+		#[cfg(feature = "bincode")]
+		Format::Bincode,
+	];
+	for format in formats {
+		let saved = SavedState::serialize(format, &strokes).unwrap();
+		assert_eq!(
+			Strokes::deserialize_with_migrate(&saved).unwrap(),
+			strokes,
+			"{format:?}"
+		);
+	}
+
+	let garbage = SavedState {
+		format: Format::Ron,
+		data: b"(__asteroids_version: 0, state: [".to_vec(),
+	};
+	assert!(Strokes::deserialize_with_migrate(&garbage).is_err());
+}
@@ -0,0 +1,208 @@
+use crate::client::ClientState;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HistoryAction {
+	Commit,
+	Undo,
+	Redo,
+}
+
+/// Undo/redo of the whole `ClientState`, as snapshots in its [`ClientState::FORMAT`] taken at commit points.
+///
+/// Keep it in your state with `#[serde(skip)]` and return it from [`ClientState::history`],
+/// then call [`History::commit`] whenever an edit is done (e.g. on `grab_stop`) and [`History::undo`]/[`History::redo`] from any callback.
+/// These get applied by `client::run` after the frame, so changes made since the last commit are lost on undo.
+#[derive(Debug)]
+pub struct History {
+	/// Snapshots before the current one, oldest first
+	undo: VecDeque<Vec<u8>>,
+	/// Snapshots undone from, most recently undone last
+	redo: Vec<Vec<u8>>,
+	/// The state as of the last commit, undo and redo
+	current: Option<Vec<u8>>,
+	/// How many snapshots to keep to undo to, the oldest get dropped first
+	limit: usize,
+	pending: Vec<HistoryAction>,
+}
+impl Default for History {
+	fn default() -> Self {
+		Self::new(64)
+	}
+}
+impl History {
+	pub fn new(limit: usize) -> Self {
+		History {
+			undo: VecDeque::new(),
+			redo: Vec::new(),
+			current: None,
+			limit,
+			pending: Vec::new(),
+		}
+	}
+
+	/// Record the state as it is at the end of this frame as an undo step, does nothing if it didn't change.
+	pub fn commit(&mut self) {
+		self.pending.push(HistoryAction::Commit);
+	}
+	/// Go back to the previous commit at the end of this frame.
+	pub fn undo(&mut self) {
+		self.pending.push(HistoryAction::Undo);
+	}
+	/// Go forward to the commit that was last undone at the end of this frame.
+	pub fn redo(&mut self) {
+		self.pending.push(HistoryAction::Redo);
+	}
+	pub fn can_undo(&self) -> bool {
+		!self.undo.is_empty()
+	}
+	pub fn can_redo(&self) -> bool {
+		!self.redo.is_empty()
+	}
+
+	/// Take the first snapshot if there isn't one yet and run the queued actions.
+	/// Returns if the state got replaced by an undo or redo.
+	pub(crate) fn apply<State: ClientState>(state: &mut State) -> bool {
+		let Some(history) = state.history() else {
+			return false;
+		};
+		let first = history.current.is_none();
+		let pending = std::mem::take(&mut history.pending);
+		if first {
+			let snapshot = State::FORMAT.serialize(&*state).ok();
+			if let Some(history) = state.history() {
+				history.current = snapshot;
+			}
+		}
+
+		let mut restored_any = false;
+		for action in pending {
+			if action == HistoryAction::Commit {
+				let Ok(snapshot) = State::FORMAT.serialize(&*state) else {
+					continue;
+				};
+				if let Some(history) = state.history() {
+					history.push(snapshot);
+				}
+				continue;
+			}
+			let Some(history) = state.history() else {
+				break;
+			};
+			let snapshot = match action {
+				HistoryAction::Undo => history.step_back(),
+				_ => history.step_forward(),
+			};
+			let Some(snapshot) = snapshot else {
+				continue;
+			};
+			match State::FORMAT.deserialize::<State>(&snapshot) {
+				Ok(restored) => {
+					// the history isn't part of the snapshot so it moves over to the restored state
+					let history = std::mem::take(history);
+					*state = restored;
+					if let Some(restored_history) = state.history() {
+						*restored_history = history;
+					}
+					restored_any = true;
+				}
+				Err(error) => tracing::error!("Couldn't restore state from history: {error}"),
+			}
+		}
+		restored_any
+	}
+
+	fn push(&mut self, snapshot: Vec<u8>) {
+		if self.current.as_ref() == Some(&snapshot) {
+			return;
+		}
+		if let Some(previous) = self.current.replace(snapshot) {
+			self.undo.push_back(previous);
+		}
+		while self.undo.len() > self.limit {
+			self.undo.pop_front();
+		}
+		self.redo.clear();
+	}
+	fn step_back(&mut self) -> Option<Vec<u8>> {
+		let previous = self.undo.pop_back()?;
+		if let Some(current) = self.current.replace(previous.clone()) {
+			self.redo.push(current);
+		}
+		Some(previous)
+	}
+	fn step_forward(&mut self) -> Option<Vec<u8>> {
+		let next = self.redo.pop()?;
+		if let Some(current) = self.current.replace(next.clone()) {
+			self.undo.push_back(current);
+		}
+		Some(next)
+	}
+}
+
+#[tokio::test]
+async fn asteroids_history() {
+	use crate::{CustomElement, Element, Reify, elements::Text};
+	use serde::{Deserialize, Serialize};
+
+	#[derive(Default, Serialize, Deserialize)]
+	struct TestState {
+		strokes: Vec<u32>,
+		#[serde(skip)]
+		history: History,
+	}
+	impl crate::Migrate for TestState {
+		type Old = Self;
+	}
+	impl ClientState for TestState {
+		const APP_ID: &'static str = "org.asteroids.history";
+
+		fn history(&mut self) -> Option<&mut History> {
+			Some(&mut self.history)
+		}
+	}
+	impl Reify for TestState {
+		fn reify(&self) -> impl Element<Self> {
+			Text::new(format!("{:?}", self.strokes)).build()
+		}
+	}
+
+	let mut state = TestState {
+		history: History::new(2),
+		..Default::default()
+	};
+	assert!(!History::apply(&mut state));
+	for stroke in 1..=3 {
+		state.strokes.push(stroke);
+		state.history.commit();
+		assert!(!History::apply(&mut state));
+	}
+	// committing without changes doesn't add an undo step
+	state.history.commit();
+	History::apply(&mut state);
+
+	state.history.undo();
+	assert!(History::apply(&mut state));
+	assert_eq!(state.strokes, [1, 2]);
+	state.history.undo();
+	History::apply(&mut state);
+	assert_eq!(state.strokes, [1]);
+	// the empty state got dropped because of the limit
+	assert!(!state.history.can_undo());
+	state.history.undo();
+	assert!(!History::apply(&mut state));
+	assert_eq!(state.strokes, [1]);
+
+	state.history.redo();
+	History::apply(&mut state);
+	assert_eq!(state.strokes, [1, 2]);
+	assert!(state.history.can_redo());
+	// a new commit forgets what was undone
+	state.strokes.push(4);
+	state.history.commit();
+	History::apply(&mut state);
+	assert!(!state.history.can_redo());
+	state.history.undo();
+	History::apply(&mut state);
+	assert_eq!(state.strokes, [1, 2]);
+}
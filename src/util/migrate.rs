@@ -1,5 +1,8 @@
-use serde::de::DeserializeOwned;
-use std::any::{Any, TypeId};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+	any::{Any, TypeId},
+	fmt::Display,
+};
 
 pub trait DeserializeAny<'de, E> {
 	fn deserialize<T: DeserializeOwned>(&self) -> Result<T, E>;
@@ -11,27 +14,245 @@ pub trait Migrate: Any + Sized + DeserializeOwned {
 	fn chain_end() -> bool {
 		TypeId::of::<Self>() == TypeId::of::<Self::Old>()
 	}
+	/// Version saved along with this type, by default 0 for the earliest version and one more than `Old` after that
+	fn version() -> u32 {
+		if Self::chain_end() {
+			0
+		} else {
+			Self::Old::version() + 1
+		}
+	}
 
-	fn deserialize_with_migrate<'de, E, D: DeserializeAny<'de, E>>(
+	/// Deserialize state saved as [`Versioned`], migrating it straight from the version it was saved with.
+	///
+	/// State saved before versions existed doesn't have a [`VersionTag`],
+	/// that gets tried as every version from newest to oldest instead.
+	fn deserialize_with_migrate<'de, E: Display, D: DeserializeAny<'de, E>>(
+		deserializer: &D,
+	) -> Result<Self, MigrateError> {
+		match deserializer.deserialize::<VersionTag>() {
+			Ok(VersionTag { version }) => Self::deserialize_version(version, deserializer),
+			Err(_) => Self::deserialize_untagged(deserializer, Vec::new()),
+		}
+	}
+	#[doc(hidden)]
+	fn deserialize_version<'de, E: Display, D: DeserializeAny<'de, E>>(
+		version: u32,
 		deserializer: &D,
-	) -> Result<Self, E> {
-		match deserializer.deserialize::<Self>() {
-			Ok(current) => Ok(current),
-			Err(e) => {
-				if Self::chain_end() {
-					Err(e)
-				} else {
-					let old = Self::Old::deserialize_with_migrate(deserializer)?;
-					Ok(old.into())
+	) -> Result<Self, MigrateError> {
+		if version == Self::version() {
+			return match deserializer.deserialize::<Versioned<Self>>() {
+				Ok(versioned) => Ok(versioned.state),
+				Err(error) => Err(MigrateError::Deserialize {
+					version,
+					type_name: std::any::type_name::<Self>(),
+					error: error.to_string(),
+				}),
+			};
+		}
+		if version > Self::version() || Self::chain_end() {
+			return Err(MigrateError::UnknownVersion {
+				version,
+				latest: Self::version(),
+			});
+		}
+		Ok(Self::Old::deserialize_version(version, deserializer)?.into())
+	}
+	#[doc(hidden)]
+	fn deserialize_untagged<'de, E: Display, D: DeserializeAny<'de, E>>(
+		deserializer: &D,
+		mut attempts: Vec<MigrateAttempt>,
+	) -> Result<Self, MigrateError> {
+		let error = match deserializer.deserialize::<Self>() {
+			Ok(current) => return Ok(current),
+			Err(error) => error,
+		};
+		attempts.push(MigrateAttempt {
+			version: Self::version(),
+			type_name: std::any::type_name::<Self>(),
+			error: error.to_string(),
+		});
+		if Self::chain_end() {
+			return Err(MigrateError::Untagged(attempts));
+		}
+		Ok(Self::Old::deserialize_untagged(deserializer, attempts)?.into())
+	}
+}
+
+/// Just the version of saved state, to know which type to deserialize the rest as.
+///
+/// Saved as `__asteroids_version` so it can't be mistaken for a `version` field of state saved before versions existed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct VersionTag {
+	#[serde(rename = "__asteroids_version")]
+	pub version: u32,
+}
+
+/// How state gets saved so it can be migrated later, e.g. `(__asteroids_version: 2, state: (...))` in RON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Versioned<T> {
+	#[serde(rename = "__asteroids_version")]
+	pub version: u32,
+	pub state: T,
+}
+impl<'a, T: Migrate> Versioned<&'a T> {
+	pub fn new(state: &'a T) -> Self {
+		Versioned {
+			version: T::version(),
+			state,
+		}
+	}
+}
+
+/// One version tried while loading state without a [`VersionTag`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrateAttempt {
+	pub version: u32,
+	pub type_name: &'static str,
+	pub error: String,
+}
+
+/// Why saved state couldn't be loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrateError {
+	/// The state was saved with a version this type's `Migrate` chain doesn't have
+	UnknownVersion { version: u32, latest: u32 },
+	/// The state's version is known but it doesn't match the type of that version
+	Deserialize {
+		version: u32,
+		type_name: &'static str,
+		error: String,
+	},
+	/// The state has no version and doesn't match any version, newest first
+	Untagged(Vec<MigrateAttempt>),
+}
+impl Display for MigrateError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			MigrateError::UnknownVersion { version, latest } => write!(
+				f,
+				"state was saved with version {version} but the latest known version is {latest}"
+			),
+			MigrateError::Deserialize {
+				version,
+				type_name,
+				error,
+			} => write!(
+				f,
+				"state version {version} isn't a valid {type_name}: {error}"
+			),
+			MigrateError::Untagged(attempts) => {
+				write!(f, "state has no version and doesn't match any version")?;
+				for attempt in attempts {
+					write!(
+						f,
+						"\n\tversion {} ({}): {}",
+						attempt.version, attempt.type_name, attempt.error
+					)?;
 				}
+				Ok(())
 			}
 		}
 	}
 }
+impl std::error::Error for MigrateError {}
+
+#[tokio::test]
+async fn asteroids_versioned_migrate() {
+	use super::{Format, SavedState};
+
+	fn ron(data: &str) -> SavedState {
+		SavedState {
+			format: Format::Ron,
+			data: data.as_bytes().to_vec(),
+		}
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct V0 {
+		name: String,
+	}
+	impl Migrate for V0 {
+		type Old = Self;
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct V1 {
+		name: String,
+		size: f32,
+	}
+	impl Migrate for V1 {
+		type Old = V0;
+	}
+	impl From<V0> for V1 {
+		fn from(old: V0) -> Self {
+			V1 {
+				name: old.name,
+				size: 1.0,
+			}
+		}
+	}
+
+	assert_eq!(V1::version(), 1);
+	let current = V1 {
+		name: "a".to_string(),
+		size: 2.0,
+	};
+	let saved = SavedState::serialize(Format::Ron, &current).unwrap();
+	assert_eq!(V1::deserialize_with_migrate(&saved).unwrap(), current);
 
-pub(crate) struct RonFile(pub String);
-impl DeserializeAny<'_, ron::Error> for RonFile {
-	fn deserialize<T: DeserializeOwned>(&self) -> Result<T, ron::Error> {
-		Ok(ron::from_str(&self.0)?)
+	let old = SavedState::serialize(
+		Format::Ron,
+		&V0 {
+			name: "b".to_string(),
+		},
+	)
+	.unwrap();
+	assert_eq!(V1::deserialize_with_migrate(&old).unwrap().size, 1.0);
+
+	// a typo doesn't get "migrated" from an older version
+	let typo = ron("(__asteroids_version: 1, state: (name: \"c\", szie: 2.0))");
+	let error = V1::deserialize_with_migrate(&typo).unwrap_err();
+	assert!(matches!(
+		error,
+		MigrateError::Deserialize { version: 1, .. }
+	));
+
+	let future = ron("(__asteroids_version: 2, state: ())");
+	assert_eq!(
+		V1::deserialize_with_migrate(&future),
+		Err(MigrateError::UnknownVersion {
+			version: 2,
+			latest: 1
+		})
+	);
+
+	// state saved before versions still loads
+	let legacy = ron("(name: \"d\")");
+	assert_eq!(V1::deserialize_with_migrate(&legacy).unwrap().size, 1.0);
+	let MigrateError::Untagged(attempts) =
+		V1::deserialize_with_migrate(&ron("(size: 1.0)")).unwrap_err()
+	else {
+		panic!("expected an untagged error");
+	};
+	assert_eq!(
+		attempts.iter().map(|a| a.version).collect::<Vec<_>>(),
+		[1, 0]
+	);
+
+	// legacy state with its own `version` field isn't taken for a version tag
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Config {
+		version: u32,
+		name: String,
+	}
+	impl Migrate for Config {
+		type Old = Self;
 	}
+	let legacy = ron("(version: 3, name: \"e\")");
+	assert_eq!(
+		Config::deserialize_with_migrate(&legacy),
+		Ok(Config {
+			version: 3,
+			name: "e".to_string()
+		})
+	);
 }
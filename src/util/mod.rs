@@ -4,5 +4,11 @@ pub use delta_set::*;
 pub mod frame_warning;
 pub use frame_warning::*;
 
+pub mod format;
+pub use format::*;
+
+pub mod history;
+pub use history::*;
+
 pub mod migrate;
 pub use migrate::*;